@@ -1,10 +1,22 @@
 //! # pipe_macros
 //! A small macro library that allows you to pipe functions
 //! similar to the pipe operator in Elixir and F# (|>)
+//!
+//! The operators a pipeline stage can use are listed in [`operators`].
+//!
+//! ## Features
+//!
+//! - `std` (enabled by default) enables the operators that need the standard library, without
+//!   it the crate is `no_std` and using one of those operators is a compile error.
+//! - `futures` enables the operators working with futures, through the `futures` crate.
+//! - `tokio` enables the operators relying on the `tokio` runtime, such as timers.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(missing_docs)]
 #![deny(warnings)]
 
+pub mod operators;
+
 /// The pipe operator |> allows you to establish "pipelines" of functions in a flexible manner.
 /// ```rust
 /// use pike::pike;
@@ -63,12 +75,24 @@
 /// };
 ///
 /// assert_eq!(len, "8");
+///
+/// // The head is a single token, any longer expression is wrapped in parentheses.
+/// let buf = pike! {
+///     (Vec::with_capacity(16))
+///     |> (tap_mut |v| v.push(1))
+/// };
+/// assert!(buf.capacity() >= 16);
 /// ```
+///
+/// # Operators
+///
+/// Besides functions, closures and macros, a stage can be one of the operators listed in
+/// [`operators`](crate::operators), such as `(take 3)` or `(into_iter)`.
 #[macro_export]
 macro_rules! pike {
     ($head:tt $(|> $funs_head:tt $(:: $funs_tail:tt)*  $(! $($bang:tt)?)?)+) => {
         {
-        let ret = $head;
+        let ret = $crate::__internal_pike_head!($head);
         $(
             let ret = $crate::__internal_pike_fun!($($($bang)? !, )? $funs_head $(:: $funs_tail)*, ret);
         )+
@@ -85,7 +109,7 @@ macro_rules! pike {
 macro_rules! pike_res {
     ($head:tt $(|> $funs_head:tt $(:: $funs_tail:tt)*)+) => {
         {
-            let ret = Ok($head);
+            let ret = Ok($crate::__internal_pike_head!($head));
             $(
                 let ret = match ret {
                     Ok(x) => $crate::__internal_pike_fun!($funs_head $(:: $funs_tail)*, x),
@@ -109,7 +133,7 @@ macro_rules! pike_opt {
             let ret = None;
             $(
                 let ret = match ret {
-                    None => $crate::__internal_pike_fun!($funs_head $(:: $funs_tail)*, $head),
+                    None => $crate::__internal_pike_fun!($funs_head $(:: $funs_tail)*, $crate::__internal_pike_head!($head)),
                     _ => ret
                 };
             )*
@@ -118,229 +142,3810 @@ macro_rules! pike_opt {
     };
 }
 
-/// Internal
+/// Works similar to `pike` but threads a context value through the pipeline.
+///
+/// The context is evaluated once and borrowed for the whole pipeline. Stages don't see it unless
+/// they opt in with `(with_ctx f)`, which calls `f(value, &ctx)`. A partial call such as
+/// `(with_ctx f(a, b))` passes the context right after the piped value: `f(value, &ctx, a, b)`.
+/// Every other stage behaves exactly as it does in `pike`.
+///
+/// Only the stages of the `pike_ctx` itself see the context. Stages running a nested pipeline,
+/// such as `(tee { ... })`, `(cond ...)` or `(diff { ... })`, run it as a plain `pike`, where
+/// `(with_ctx f)` is a compile error. Borrow the context in a closure to use it there instead.
+/// ```rust
+/// use pike::pike_ctx;
+/// struct Config {
+///     factor: u32,
+///     offset: u32,
+/// }
+///
+/// fn scale(n: u32, config: &Config) -> u32 {
+///     n * config.factor
+/// }
+///
+/// fn shift(n: u32, config: &Config, times: u32) -> u32 {
+///     n + config.offset * times
+/// }
+///
+/// let config = Config { factor: 3, offset: 1 };
+/// let num = pike_ctx! {
+///     config;
+///     2
+///     |> (with_ctx scale)
+///     |> (with_ctx shift(2))
+///     |> (with_ctx |n, config: &Config| n - config.factor)
+/// };
+/// assert_eq!(num, 5);
+/// ```
 #[macro_export]
-macro_rules! __internal_pike_fun {
-    (&, $ret:expr) => {
-        &$ret
-    };
-    ((as $typ:ty), $ret:expr) => {
-        $ret as $typ
-    };
-    (($funs_head:tt $(:: $funs_tail:tt)* ($($arg:expr),*)), $ret:expr) => {
-        $funs_head $(:: $funs_tail)* ($ret $(,$arg)*)
-    };
-    (($fun:ident!($($arg:expr),*)), $ret:expr) => {
-        $fun!($ret $(,$arg)*)
-    };
-    (($fun:expr), $ret:expr) => {
-        $fun($ret)
-    };
-    ($fun:path, $ret:expr) => {
-        $fun($ret)
-    };
-    (!, $fun:ident, $ret:expr) => {
-        $fun!($ret)
+macro_rules! pike_ctx {
+    ($ctx:expr; $head:tt $(|> $funs_head:tt $(:: $funs_tail:tt)*  $(! $($bang:tt)?)?)+) => {
+        {
+        let ctx = &$ctx;
+        let ret = $crate::__internal_pike_head!($head);
+        $(
+            let ret = $crate::__internal_pike_ctx_fun!(ctx, $($($bang)? !, )? $funs_head $(:: $funs_tail)*, ret);
+        )+
+        ret
+        }
     }
 }
 
-#[cfg(test)]
-mod test_pike_opt {
-    fn times2(a: u32) -> Option<u32> {
-        return Some(a * 2);
-    }
-
-    fn nope(_a: u32) -> Option<u32> {
-        return None;
-    }
-
-    #[test]
-    fn accepts_options() {
-        let ret = pike_opt!(4 |> times2);
-
-        assert_eq!(ret, Some(8));
-    }
-
-    #[test]
-    fn accepts_unwrap() {
-        let ret = pike_opt!(4 |> times2).unwrap();
-
-        assert_eq!(ret, 8);
-    }
-
-    #[test]
-    fn exits_early() {
-        let ret = pike_opt! {
-            4
-            |> times2
-            |> times2
-            |> times2
-        };
-
-        assert_eq!(ret, Some(8));
-    }
-
-    #[test]
-    fn goes_until_some() {
-        let ret = pike_opt! {
-            4
-            |> nope
-            |> nope
-            |> (|_i: u32| None)
-            |> times2
-            |> nope
-        };
-
-        assert_eq!(ret, Some(8));
-    }
-
-    #[test]
-    fn ends_with_none() {
-        let ret = pike_opt! {
-            4
-            |> nope
-            |> nope
-            |> (|_i| None)
-            |> nope
-        };
-
-        assert_eq!(ret, None);
+/// Works similar to `pike` but starts from several values at once.
+///
+/// The head is a tuple whose elements are spread into the arguments of the first stage, which can
+/// be a function, a closure or a partial call such as `(f(extra))` becoming `f(a, b, c, extra)`.
+/// From there on the pipeline behaves exactly as `pike`. A tuple head in `pike` itself is passed
+/// as a single tuple value instead, so `pike!((a, b) |> f)` calls `f((a, b))`.
+/// ```rust
+/// use pike::pike_zip;
+/// fn sum3(a: u32, b: u32, c: u32) -> u32 {
+///     a + b + c
+/// }
+///
+/// let num = pike_zip!((1, 2, 3) |> sum3 |> (|n: u32| n * 2));
+/// assert_eq!(num, 12);
+/// ```
+#[macro_export]
+macro_rules! pike_zip {
+    (($($arg:expr),+ $(,)?) |> $first_head:tt $(:: $first_tail:tt)* $(|> $funs_head:tt $(:: $funs_tail:tt)*  $(! $($bang:tt)?)?)*) => {
+        {
+        let ret = $crate::__internal_pike_zip_fun!($first_head $(:: $first_tail)*, ($($arg),+));
+        $(
+            let ret = $crate::__internal_pike_fun!($($($bang)? !, )? $funs_head $(:: $funs_tail)*, ret);
+        )*
+        ret
+        }
     }
 }
 
-#[cfg(test)]
-mod test_pike_res {
-    fn times2(a: u32) -> Result<u32, String> {
-        return Ok(a * 2);
-    }
+/// Internal
+#[macro_export]
+macro_rules! __internal_pike_zip_fun {
+    (($funs_head:tt $(:: $funs_tail:tt)* ($($extra:expr),*)), ($($arg:expr),+)) => {
+        $funs_head $(:: $funs_tail)* ($($arg),+ $(,$extra)*)
+    };
+    (($fun:expr), ($($arg:expr),+)) => {
+        ($fun)($($arg),+)
+    };
+    ($fun:path, ($($arg:expr),+)) => {
+        $fun($($arg),+)
+    };
+}
 
-    fn fail_if_over_4(a: u32) -> Result<u32, String> {
-        if a > 4 {
-            return Err("This number is larger than four".to_string());
+/// Works similar to `pike` but returns the head alongside the result, as `(head, result)`.
+///
+/// The head is evaluated once and the stages run on a clone of it, so its type has to be `Clone`.
+/// Useful for keeping the input of a pipeline around for comparisons or error messages without
+/// binding it first.
+/// ```rust
+/// use pike::pike_pair;
+/// let (input, len) = pike_pair!("pike" |> str::len);
+/// assert_eq!((input, len), ("pike", 4));
+/// ```
+#[macro_export]
+macro_rules! pike_pair {
+    ($head:tt $($stages:tt)+) => {
+        {
+        let original = $crate::__internal_pike_head!($head);
+        let ret = ::core::clone::Clone::clone(&original);
+        (original, $crate::pike!(ret $($stages)+))
         }
-        return Ok(a);
-    }
-
-    #[test]
-    fn accepts_results() {
-        let ret = pike_res!(4 |> times2);
-
-        assert_eq!(ret, Ok(8));
-    }
-
-    #[test]
-    fn accepts_unwrap() {
-        let ret = pike_res!(4 |> times2).unwrap();
-
-        assert_eq!(ret, 8);
-    }
-
-    #[test]
-    fn chains_result_values() {
-        let ret = pike_res! {
-            4
-            |> times2
-            |> times2
-            |> times2
-        };
-
-        assert_eq!(ret, Ok(32));
-    }
-
-    #[test]
-    fn exits_early() {
-        let ret = pike_res! {
-            4
-            |> times2
-            |> fail_if_over_4
-            |> times2
-            |> times2
-        };
-
-        assert_eq!(ret, Err("This number is larger than four".to_string()));
-    }
+    };
 }
 
-#[cfg(test)]
-mod test_pike {
-    fn times2(a: u32) -> u32 {
-        return a * 2;
-    }
-
-    fn times(a: u32, b: u32, c: u32) -> u32 {
-        return a * b * c;
-    }
-
-    #[test]
-    fn test_int() {
-        let multiply = |i: u32| i * 2;
-        let ret = pike! {
-            4
-            |> times2
-            |> (|i: u32| i * 2)
-            |> multiply
-            |> (times(100, 10))
-        };
-
-        assert_eq!(ret, 32000);
+/// Runs a `pike` pipeline the given number of times and prints the average duration of a run to
+/// stderr, returning the result of the last run.
+///
+/// The head and the result of every run are passed through `std::hint::black_box` so the
+/// pipeline isn't optimized away. Since the head is evaluated once per run it has to be an
+/// expression that can be evaluated repeatedly, like a `Copy` value or `(seed.clone())`.
+/// This is meant for a rough idea of where time goes, not as a replacement for a benchmarking
+/// harness such as `criterion`. Requires the `std` feature.
+/// ```rust
+/// use pike::pike_bench;
+/// fn times2(n: u32) -> u32 {
+///     n * 2
+/// }
+///
+/// let num = pike_bench!(100, 2 |> times2 |> times2);
+/// assert_eq!(num, 8);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! pike_bench {
+    ($iterations:expr, $head:tt $(|> $funs_head:tt $(:: $funs_tail:tt)*  $(! $($bang:tt)?)?)+) => {
+        {
+        let iterations: u32 = $iterations;
+        assert!(iterations > 0, "pike_bench needs at least one iteration");
+        let mut last = None;
+        let start = ::std::time::Instant::now();
+        for _ in 0..iterations {
+            let ret = ::std::hint::black_box($crate::__internal_pike_head!($head));
+            $(
+                let ret = $crate::__internal_pike_fun!($($($bang)? !, )? $funs_head $(:: $funs_tail)*, ret);
+            )+
+            last = Some(::std::hint::black_box(ret));
+        }
+        ::std::eprintln!(
+            "pike_bench: {:?} per iteration over {} iterations",
+            start.elapsed() / iterations,
+            iterations
+        );
+        last.unwrap()
+        }
     }
+}
 
-    #[test]
-    fn test_string() {
-        let ret = pike! {
-            "abcd"
-            |> str::len
-            |> (as u32)
-            |> times2
-            |> (times(100, 10))
-            |> &
-            |> u32::to_string
-        };
-
-        //let ret = "abcd";
-        //let ret = ret.len();
-        //let ret = ret as u32;
-        //let ret = times2(ret);
-        //let ret = times(ret, 100, 10);
-        //let ret = ret.to_string();
+/// Internal
+#[macro_export]
+macro_rules! __internal_pike_head {
+    (($head:expr)) => {
+        $head
+    };
+    ($head:tt) => {
+        $head
+    };
+}
 
-        assert_eq!(ret, times(times2("abcd".len() as u32), 100, 10).to_string());
-        assert_eq!(ret, "8000");
-    }
+/// Internal
+#[macro_export]
+macro_rules! __internal_pike_ctx_fun {
+    ($ctx:ident, (with_ctx $($fun:ident)::+ ($($arg:expr),*)), $ret:expr) => {
+        $($fun)::+ ($ret, $ctx $(,$arg)*)
+    };
+    ($ctx:ident, (with_ctx $fun:expr), $ret:expr) => {
+        ($fun)($ret, $ctx)
+    };
+    ($ctx:ident, $($stage:tt)+) => {
+        $crate::__internal_pike_fun!($($stage)+)
+    };
+}
 
-    #[test]
-    fn test_macro() {
-        let mut ret;
-        macro_rules! test_macro_a {
-            ($it:expr) => {
-                ret = Some($it)
-            };
-        }
+/// Internal
+#[macro_export]
+macro_rules! __internal_pike_operator_call {
+    ($op:ident) => {
+        ::core::compile_error!(::core::concat!(
+            "`(",
+            ::core::stringify!($op),
+            "(..))` is ambiguous, write the `",
+            ::core::stringify!($op),
+            "` operator without parentheses around its arguments or call a function of that name by path, e.g. `(self::",
+            ::core::stringify!($op),
+            "(..))`"
+        ))
+    };
+}
 
-        macro_rules! test_macro_b {
-            ($it:expr, $arg:expr) => {
-                ret = Some($it * $arg)
-            };
-        }
-        let multiply = |i: u32| i * 2;
-        pike! {
-            4
-            |> times2
-            |> (|i: u32| i * 2)
+/// Internal
+#[macro_export]
+macro_rules! __internal_pike_fun {
+    // An operator name directly followed by parentheses reads as both the operator and a partial
+    // call, so it is rejected before the partial call arm below can pick a meaning.
+    ((take ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(take)
+    };
+    ((skip ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(skip)
+    };
+    ((replace ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(replace)
+    };
+    ((eq ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(eq)
+    };
+    ((ne ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(ne)
+    };
+    ((lt ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(lt)
+    };
+    ((le ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(le)
+    };
+    ((gt ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(gt)
+    };
+    ((ge ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(ge)
+    };
+    ((windows ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(windows)
+    };
+    ((chunks ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(chunks)
+    };
+    ((expect_with ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(expect_with)
+    };
+    ((catch_unwind ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(catch_unwind)
+    };
+    ((group_by ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(group_by)
+    };
+    ((join ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(join)
+    };
+    ((guard ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(guard)
+    };
+    ((scan ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(scan)
+    };
+    ((satisfies ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(satisfies)
+    };
+    ((flat_map ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(flat_map)
+    };
+    ((or ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(or)
+    };
+    ((step_by ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(step_by)
+    };
+    ((tap_mut ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(tap_mut)
+    };
+    ((on_some ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(on_some)
+    };
+    ((on_ok ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(on_ok)
+    };
+    ((position ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(position)
+    };
+    ((find ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(find)
+    };
+    ((any ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(any)
+    };
+    ((all ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(all)
+    };
+    ((reduce ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(reduce)
+    };
+    ((max_by_key ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(max_by_key)
+    };
+    ((min_by_key ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(min_by_key)
+    };
+    ((try_fold ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(try_fold)
+    };
+    ((pow ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(pow)
+    };
+    ((split_at ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(split_at)
+    };
+    ((checked_add ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(checked_add)
+    };
+    ((checked_sub ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(checked_sub)
+    };
+    ((checked_mul ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(checked_mul)
+    };
+    ((checked_div ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(checked_div)
+    };
+    ((saturating_add ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(saturating_add)
+    };
+    ((saturating_sub ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(saturating_sub)
+    };
+    ((saturating_mul ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(saturating_mul)
+    };
+    ((wrapping_add ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(wrapping_add)
+    };
+    ((wrapping_sub ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(wrapping_sub)
+    };
+    ((wrapping_mul ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(wrapping_mul)
+    };
+    ((apply ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(apply)
+    };
+    ((split ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(split)
+    };
+    ((send ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(send)
+    };
+    ((recover ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(recover)
+    };
+    ((zip_with ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(zip_with)
+    };
+    ((map_or ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(map_or)
+    };
+    ((timeout ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(timeout)
+    };
+    ((chunk_by ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(chunk_by)
+    };
+    ((assert_matches ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(assert_matches)
+    };
+    ((from_str_radix ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(from_str_radix)
+    };
+    ((tap_err ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(tap_err)
+    };
+    ((memoize ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(memoize)
+    };
+    ((scan_collect ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(scan_collect)
+    };
+    ((get_or_insert_with ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(get_or_insert_with)
+    };
+    ((timed ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(timed)
+    };
+    ((chain ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(chain)
+    };
+    ((retry_with ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(retry_with)
+    };
+    ((tap_if ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(tap_if)
+    };
+    ((into_result ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(into_result)
+    };
+    ((dbg_if ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(dbg_if)
+    };
+    ((tee_to ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(tee_to)
+    };
+    ((select ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(select)
+    };
+    ((map_while ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(map_while)
+    };
+    ((take_while ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(take_while)
+    };
+    ((skip_while ($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_operator_call!(skip_while)
+    };
+    (($fun:ident ($($arg:expr),*)), $ret:expr) => {
+        $fun($ret $(,$arg)*)
+    };
+    (&, $ret:expr) => {
+        &$ret
+    };
+    ((as $typ:ty), $ret:expr) => {
+        $ret as $typ
+    };
+    ((replace $from:expr, $to:expr), $ret:expr) => {
+        $crate::__internal_pike_std!((replace $from, $to), $ret)
+    };
+    ((replace $with:expr), $ret:expr) => {
+        {
+            ::core::mem::drop($ret);
+            $with
+        }
+    };
+    ((eq $rhs:expr), $ret:expr) => {
+        $ret == $rhs
+    };
+    ((ne $rhs:expr), $ret:expr) => {
+        $ret != $rhs
+    };
+    ((lt $rhs:expr), $ret:expr) => {
+        $ret < $rhs
+    };
+    ((le $rhs:expr), $ret:expr) => {
+        $ret <= $rhs
+    };
+    ((gt $rhs:expr), $ret:expr) => {
+        $ret > $rhs
+    };
+    ((ge $rhs:expr), $ret:expr) => {
+        $ret >= $rhs
+    };
+    ((iter), $ret:expr) => {
+        $ret.iter()
+    };
+    ((into_iter), $ret:expr) => {
+        ::core::iter::IntoIterator::into_iter($ret)
+    };
+    ((collect $(::<$typ:ty>)?), $ret:expr) => {
+        $ret.collect $(::<$typ>)? ()
+    };
+    ((take $n:expr), $ret:expr) => {
+        $ret.take($n)
+    };
+    ((skip $n:expr), $ret:expr) => {
+        $ret.skip($n)
+    };
+    ((< $typ:ty as $trait:path > :: $method:ident $(($($arg:expr),*))?), $ret:expr) => {
+        <$typ as $trait>::$method($ret $($(, $arg)*)?)
+    };
+    ((flatten), $ret:expr) => {
+        $ret.flatten()
+    };
+    ((sort), $ret:expr) => {
+        {
+            let mut sorted = $ret;
+            sorted.sort();
+            sorted
+        }
+    };
+    ((sorted), $ret:expr) => {
+        $crate::__internal_pike_std!((sorted), $ret)
+    };
+    ((windows $n:expr), $ret:expr) => {
+        $ret.windows($n)
+    };
+    ((chunks $n:expr), $ret:expr) => {
+        $ret.chunks($n)
+    };
+    ((expect_with $f:expr), $ret:expr) => {
+        $crate::__private::ExpectWith::expect_with($ret, $f)
+    };
+    ((recover_default), $ret:expr) => {
+        $ret.unwrap_or_default()
+    };
+    ((catch_unwind $($arg:tt)*), $ret:expr) => {
+        $crate::__internal_pike_std!((catch_unwind $($arg)*), $ret)
+    };
+    ((counts), $ret:expr) => {
+        $crate::__internal_pike_std!((counts), $ret)
+    };
+    ((group_by $($arg:tt)*), $ret:expr) => {
+        $crate::__internal_pike_std!((group_by $($arg)*), $ret)
+    };
+    ((dyn $trait:ident :: $method:ident $(($($arg:expr),*))?), $ret:expr) => {
+        <dyn $trait as $trait>::$method(&*$ret $($(, $arg)*)?)
+    };
+    ((join $($arg:tt)*), $ret:expr) => {
+        $crate::__internal_pike_std!((join $($arg)*), $ret)
+    };
+    ((guard $pred:expr, $err:expr), $ret:expr) => {
+        $crate::__private::guard($ret, $pred, || $err)
+    };
+    ((enumerate), $ret:expr) => {
+        $ret.enumerate()
+    };
+    ((rev), $ret:expr) => {
+        $ret.rev()
+    };
+    ((scan $state:expr, $f:expr), $ret:expr) => {
+        $ret.scan($state, $f)
+    };
+    ((. $method:ident $(::<$($gen:ty),*>)? ($($arg:expr),*)), $ret:expr) => {
+        $ret.$method $(::<$($gen),*>)? ($($arg),*)
+    };
+    ((join_all), $ret:expr) => {
+        $crate::__internal_pike_futures!((join_all), $ret)
+    };
+    ((satisfies $pred:expr), $ret:expr) => {
+        $crate::__private::satisfies($ret, $pred, ::core::stringify!($pred))
+    };
+    ((try_block $name:ident => { $($body:tt)* }), $ret:expr) => {
+        {
+            let $name = $ret;
+            (|| -> ::core::result::Result<_, _> { $($body)* })()
+        }
+    };
+    ((flat_map $f:expr), $ret:expr) => {
+        $ret.flat_map($f)
+    };
+    ((dedup), $ret:expr) => {
+        $crate::__internal_pike_std!((dedup), $ret)
+    };
+    ((unique), $ret:expr) => {
+        $crate::__internal_pike_std!((unique), $ret)
+    };
+    ((or $value:expr), $ret:expr) => {
+        $ret.or($value)
+    };
+    ((step_by $n:expr), $ret:expr) => {
+        $ret.step_by($n)
+    };
+    ((tap_mut $f:expr), $ret:expr) => {
+        $crate::__private::tap_mut($ret, $f)
+    };
+    ((on_some $f:expr), $ret:expr) => {
+        ::core::option::Option::map($ret, $f)
+    };
+    ((on_ok $f:expr), $ret:expr) => {
+        ::core::result::Result::map($ret, $f)
+    };
+    ((array_from_fn), $ret:expr) => {
+        ::core::array::from_fn($ret)
+    };
+    ((tee { $($stages:tt)+ }), $ret:expr) => {
+        {
+            let value = $ret;
+            let side = ::core::clone::Clone::clone(&value);
+            let _ = $crate::pike!(side $($stages)+);
+            value
+        }
+    };
+    ((position $pred:expr), $ret:expr) => {
+        {
+            let mut iter = $ret;
+            iter.position($pred)
+        }
+    };
+    ((find $pred:expr), $ret:expr) => {
+        {
+            let mut iter = $ret;
+            iter.find($pred)
+        }
+    };
+    ((any $pred:expr), $ret:expr) => {
+        {
+            let mut iter = $ret;
+            iter.any($pred)
+        }
+    };
+    ((all $pred:expr), $ret:expr) => {
+        {
+            let mut iter = $ret;
+            iter.all($pred)
+        }
+    };
+    ((let_else $($rest:tt)+), $ret:expr) => {
+        $crate::__internal_pike_let_else!([] [$($rest)+], $ret)
+    };
+    ((reduce $f:expr), $ret:expr) => {
+        $ret.reduce($f)
+    };
+    ((cycle), $ret:expr) => {
+        $ret.cycle()
+    };
+    ((peekable), $ret:expr) => {
+        $ret.peekable()
+    };
+    ((max_by_key $key:expr), $ret:expr) => {
+        $ret.max_by_key($key)
+    };
+    ((min_by_key $key:expr), $ret:expr) => {
+        $ret.min_by_key($key)
+    };
+    ((try_fold $init:expr, $f:expr), $ret:expr) => {
+        {
+            let mut iter = $ret;
+            iter.try_fold($init, $f)
+        }
+    };
+    ((into_res { $($stages:tt)+ }), $ret:expr) => {
+        match $ret {
+            Ok(value) => $crate::pike_res!(value $($stages)+),
+            Err(err) => Err(err),
+        }
+    };
+    ((abs), $ret:expr) => {
+        $ret.abs()
+    };
+    ((signum), $ret:expr) => {
+        $ret.signum()
+    };
+    ((pow $n:expr), $ret:expr) => {
+        $ret.pow($n)
+    };
+    ((split_at $n:expr), $ret:expr) => {
+        $ret.split_at($n)
+    };
+    ((first), $ret:expr) => {
+        $ret.first()
+    };
+    ((last), $ret:expr) => {
+        $ret.last()
+    };
+    ((checked_add $x:expr), $ret:expr) => {
+        $ret.checked_add($x)
+    };
+    ((checked_sub $x:expr), $ret:expr) => {
+        $ret.checked_sub($x)
+    };
+    ((checked_mul $x:expr), $ret:expr) => {
+        $ret.checked_mul($x)
+    };
+    ((checked_div $x:expr), $ret:expr) => {
+        $ret.checked_div($x)
+    };
+    ((saturating_add $x:expr), $ret:expr) => {
+        $ret.saturating_add($x)
+    };
+    ((saturating_sub $x:expr), $ret:expr) => {
+        $ret.saturating_sub($x)
+    };
+    ((saturating_mul $x:expr), $ret:expr) => {
+        $ret.saturating_mul($x)
+    };
+    ((wrapping_add $x:expr), $ret:expr) => {
+        $ret.wrapping_add($x)
+    };
+    ((wrapping_sub $x:expr), $ret:expr) => {
+        $ret.wrapping_sub($x)
+    };
+    ((wrapping_mul $x:expr), $ret:expr) => {
+        $ret.wrapping_mul($x)
+    };
+    ((dbg_type), $ret:expr) => {
+        $crate::__internal_pike_std!((dbg_type), $ret)
+    };
+    ((apply $fun:expr), $ret:expr) => {
+        ($fun)($ret)
+    };
+    ((split $sep:expr), $ret:expr) => {
+        $ret.split($sep)
+    };
+    ((lines), $ret:expr) => {
+        $ret.lines()
+    };
+    ((chars), $ret:expr) => {
+        $ret.chars()
+    };
+    ((bytes), $ret:expr) => {
+        $ret.bytes()
+    };
+    ((trim), $ret:expr) => {
+        $ret.trim()
+    };
+    ((to_lowercase), $ret:expr) => {
+        $crate::__internal_pike_std!((to_lowercase), $ret)
+    };
+    ((to_uppercase), $ret:expr) => {
+        $crate::__internal_pike_std!((to_uppercase), $ret)
+    };
+    ((parse $(::<$typ:ty>)?), $ret:expr) => {
+        $ret.parse $(::<$typ>)? ()
+    };
+    ((send $tx:expr), $ret:expr) => {
+        $crate::__internal_pike_std!((send $tx), $ret)
+    };
+    ((recover $f:expr), $ret:expr) => {
+        $ret.unwrap_or_else($f)
+    };
+    ((zip_with $other:expr, $f:expr), $ret:expr) => {
+        $crate::__private::zip_with($ret, $other, $f)
+    };
+    ((as_bytes), $ret:expr) => {
+        $ret.as_bytes()
+    };
+    ((from_utf8), $ret:expr) => {
+        ::core::str::from_utf8($ret)
+    };
+    ((map_or $default:expr, $f:expr), $ret:expr) => {
+        $ret.map_or($default, $f)
+    };
+    ((transpose), $ret:expr) => {
+        $ret.transpose()
+    };
+    ((build $(. $method:ident $(::<$($gen:ty),*>)? ($($arg:expr),*))+), $ret:expr) => {
+        {
+            #[allow(unused_mut)]
+            let mut builder = $ret;
+            builder $(.$method $(::<$($gen),*>)? ($($arg),*))+
+        }
+    };
+    ((flatten_errors), $ret:expr) => {
+        $crate::__internal_pike_std!((flatten_errors), $ret)
+    };
+    ((cond $pred:expr => { $($then:tt)+ } else { $($else:tt)+ }), $ret:expr) => {
+        {
+            let value = $ret;
+            if $crate::__private::holds(&value, $pred) {
+                $crate::pike!(value $($then)+)
+            } else {
+                $crate::pike!(value $($else)+)
+            }
+        }
+    };
+    ((timeout $($arg:tt)*), $ret:expr) => {
+        $crate::__internal_pike_tokio!((timeout $($arg)*), $ret)
+    };
+    ((debug_pretty), $ret:expr) => {
+        $crate::__internal_pike_std!((debug_pretty), $ret)
+    };
+    ((chunk_by $pred:expr), $ret:expr) => {
+        $ret.chunk_by($pred)
+    };
+    ((swap_bytes), $ret:expr) => {
+        $ret.swap_bytes()
+    };
+    ((to_be), $ret:expr) => {
+        $ret.to_be()
+    };
+    ((to_le), $ret:expr) => {
+        $ret.to_le()
+    };
+    ((assert_matches $pat:pat), $ret:expr) => {
+        {
+            let value = $ret;
+            ::core::assert!(
+                ::core::matches!(&value, $pat),
+                "{}",
+                ::core::concat!(
+                    "pipeline value does not match `(assert_matches ",
+                    ::core::stringify!($pat),
+                    ")`"
+                )
+            );
+            value
+        }
+    };
+    ((to_vec), $ret:expr) => {
+        $crate::__internal_pike_std!((to_vec), $ret)
+    };
+    ((probe), $ret:expr) => {
+        $crate::__internal_pike_std!((probe), $ret)
+    };
+    ((diff { $($stages:tt)+ }), $ret:expr) => {
+        {
+            let value = $ret;
+            let original = ::core::clone::Clone::clone(&value);
+            (original, $crate::pike!(value $($stages)+))
+        }
+    };
+    ((ceil), $ret:expr) => {
+        $crate::__internal_pike_std!((ceil), $ret)
+    };
+    ((floor), $ret:expr) => {
+        $crate::__internal_pike_std!((floor), $ret)
+    };
+    ((round), $ret:expr) => {
+        $crate::__internal_pike_std!((round), $ret)
+    };
+    ((sqrt), $ret:expr) => {
+        $crate::__internal_pike_std!((sqrt), $ret)
+    };
+    ((ln), $ret:expr) => {
+        $crate::__internal_pike_std!((ln), $ret)
+    };
+    ((exp), $ret:expr) => {
+        $crate::__internal_pike_std!((exp), $ret)
+    };
+    ((from_str_radix::<$typ:ty> $radix:expr), $ret:expr) => {
+        <$typ>::from_str_radix($ret, $radix)
+    };
+    ((from_str_radix $radix:expr), $ret:expr) => {
+        i64::from_str_radix($ret, $radix)
+    };
+    ((collect_result::<$typ:ty>), $ret:expr) => {
+        $ret.collect::<::core::result::Result<$typ, _>>()
+    };
+    ((collect_result), $ret:expr) => {
+        $ret.collect::<::core::result::Result<_, _>>()
+    };
+    ((tap_err $f:expr), $ret:expr) => {
+        ::core::result::Result::inspect_err($ret, $f)
+    };
+    ((memoize $f:expr), $ret:expr) => {
+        $crate::__internal_pike_std!((memoize $f), $ret)
+    };
+    ((validate), $ret:expr) => {
+        $crate::PikeValidate::validate($ret)
+    };
+    ((scan_collect $($arg:tt)*), $ret:expr) => {
+        $crate::__internal_pike_std!((scan_collect $($arg)*), $ret)
+    };
+    ((get_or_insert_with $f:expr), $ret:expr) => {
+        $ret.get_or_insert_with($f)
+    };
+    ((timed $($arg:tt)*), $ret:expr) => {
+        $crate::__internal_pike_std!((timed $($arg)*), $ret)
+    };
+    ((chain $other:expr), $ret:expr) => {
+        $ret.chain($other)
+    };
+    ((unzip::<$left:ty, $right:ty>), $ret:expr) => {
+        ::core::iter::Iterator::unzip::<_, _, $left, $right>($ret)
+    };
+    ((unzip), $ret:expr) => {
+        ::core::iter::Iterator::unzip($ret)
+    };
+    ((hex), $ret:expr) => {
+        $crate::__internal_pike_std!((hex), $ret)
+    };
+    ((retry_with $($arg:tt)*), $ret:expr) => {
+        $crate::__internal_pike_tokio!((retry_with $($arg)*), $ret)
+    };
+    ((tap_if $pred:expr, $f:expr), $ret:expr) => {
+        $crate::__private::tap_if($ret, $pred, $f)
+    };
+    ((into_result $err:expr), $ret:expr) => {
+        ::core::option::Option::ok_or($ret, $err)
+    };
+    ((dbg_if $($arg:tt)*), $ret:expr) => {
+        $crate::__internal_pike_std!((dbg_if $($arg)*), $ret)
+    };
+    ((as_slice), $ret:expr) => {
+        $ret.as_slice()
+    };
+    ((to_array::<$len:tt>), $ret:expr) => {
+        <[_; $len] as ::core::convert::TryFrom<_>>::try_from($ret)
+    };
+    ((tee_to $out:expr), $ret:expr) => {{
+        let value = $ret;
+        $out = ::core::clone::Clone::clone(&value);
+        value
+    }};
+    ((. $field:tt), $ret:expr) => {
+        $ret.$field
+    };
+    ((select $index:tt), $ret:expr) => {
+        $ret.$index
+    };
+    ((map_while $f:expr), $ret:expr) => {
+        $ret.map_while($f)
+    };
+    ((take_while $pred:expr), $ret:expr) => {
+        $ret.take_while($pred)
+    };
+    ((skip_while $pred:expr), $ret:expr) => {
+        $ret.skip_while($pred)
+    };
+    ((with_ctx $($fun:tt)*), $ret:expr) => {
+        ::core::compile_error!(
+            "`(with_ctx ..)` only works in the stages of a `pike_ctx`, not in nested pipelines"
+        )
+    };
+    ((move $($closure:tt)*), $ret:expr) => {
+        (move $($closure)*)($ret)
+    };
+    (($($fun:ident)::+ | $($closure:tt)*), $ret:expr) => {
+        $($fun)::+ ($ret, | $($closure)*)
+    };
+    (($($fun:ident)::+ || $($closure:tt)*), $ret:expr) => {
+        $($fun)::+ ($ret, || $($closure)*)
+    };
+    (($($fun:ident)::+ move $($closure:tt)*), $ret:expr) => {
+        $($fun)::+ ($ret, move $($closure)*)
+    };
+    (($funs_head:tt $(:: $funs_tail:tt)* ($($arg:expr),*)), $ret:expr) => {
+        $funs_head $(:: $funs_tail)* ($ret $(,$arg)*)
+    };
+    (($fun:ident!($($arg:tt)*)), $ret:expr) => {
+        $crate::__internal_pike_macro!($fun, paren, [] [$($arg)*], $ret)
+    };
+    (($fun:ident![$($arg:tt)*]), $ret:expr) => {
+        $crate::__internal_pike_macro!($fun, bracket, [] [$($arg)*], $ret)
+    };
+    (($fun:ident!{$($arg:tt)*}), $ret:expr) => {
+        $crate::__internal_pike_macro!($fun, brace, [] [$($arg)*], $ret)
+    };
+    (($fun:expr), $ret:expr) => {
+        $fun($ret)
+    };
+    ($fun:path, $ret:expr) => {
+        $fun($ret)
+    };
+    (!, $fun:ident, $ret:expr) => {
+        $fun!($ret)
+    }
+}
+
+/// Internal
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! __internal_pike_std {
+    ((sorted), $ret:expr) => {{
+        let mut sorted: ::std::vec::Vec<_> = ::core::iter::Iterator::collect($ret);
+        sorted.sort();
+        sorted
+    }};
+    ((catch_unwind $fun:expr), $ret:expr) => {
+        ::std::panic::catch_unwind(|| ($fun)($ret))
+    };
+    ((join $sep:expr), $ret:expr) => {
+        ::core::iter::Iterator::collect::<::std::vec::Vec<_>>($ret).join($sep)
+    };
+    ((dedup), $ret:expr) => {{
+        let mut deduped: ::std::vec::Vec<_> = ::core::convert::Into::into($ret);
+        deduped.dedup();
+        deduped
+    }};
+    ((unique), $ret:expr) => {{
+        let mut seen = ::std::collections::HashSet::new();
+        ::core::iter::Iterator::collect::<::std::vec::Vec<_>>(
+            ::core::iter::IntoIterator::into_iter($ret)
+                .filter(|item| seen.insert(::core::clone::Clone::clone(item))),
+        )
+    }};
+    ((counts), $ret:expr) => {{
+        let mut counts = ::std::collections::HashMap::<_, usize>::new();
+        for item in $ret {
+            *counts.entry(item).or_insert(0) += 1;
+        }
+        counts
+    }};
+    ((group_by $key:expr), $ret:expr) => {{
+        let key = &mut $key;
+        let mut groups = ::std::collections::HashMap::<_, ::std::vec::Vec<_>>::new();
+        for item in $ret {
+            groups.entry(key(&item)).or_default().push(item);
+        }
+        groups
+    }};
+    ((dbg_type), $ret:expr) => {{
+        let value = $ret;
+        ::std::eprintln!(
+            "[{}:{}:{}] {}",
+            ::core::file!(),
+            ::core::line!(),
+            ::core::column!(),
+            ::core::any::type_name_of_val(&value)
+        );
+        value
+    }};
+    ((probe), $ret:expr) => {{
+        let value = $ret;
+        ::std::eprintln!(
+            "[{}:{}:{}] {:?}: {}",
+            ::core::file!(),
+            ::core::line!(),
+            ::core::column!(),
+            &value,
+            ::core::any::type_name_of_val(&value)
+        );
+        value
+    }};
+    ((debug_pretty), $ret:expr) => {{
+        let value = $ret;
+        ::std::eprintln!(
+            "[{}:{}:{}] {:#?}",
+            ::core::file!(),
+            ::core::line!(),
+            ::core::column!(),
+            &value
+        );
+        value
+    }};
+    ((hex), $ret:expr) => {{
+        let value = $ret;
+        ::std::eprintln!(
+            "[{}:{}:{}] {:#x}",
+            ::core::file!(),
+            ::core::line!(),
+            ::core::column!(),
+            &value
+        );
+        value
+    }};
+    ((dbg_if $pred:expr), $ret:expr) => {{
+        let value = $ret;
+        if $crate::__private::holds(&value, $pred) {
+            ::std::eprintln!(
+                "[{}:{}:{}] {:?}",
+                ::core::file!(),
+                ::core::line!(),
+                ::core::column!(),
+                &value
+            );
+        }
+        value
+    }};
+    ((to_vec), $ret:expr) => {
+        $ret.to_vec()
+    };
+    ((replace $from:expr, $to:expr), $ret:expr) => {
+        $ret.replace($from, $to)
+    };
+    ((memoize $f:expr), $ret:expr) => {{
+        ::std::thread_local! {
+            static CACHE: ::core::cell::RefCell<$crate::__private::MemoCache> =
+                ::core::cell::RefCell::new(::core::default::Default::default());
+        }
+        $crate::__private::memoize(&CACHE, $ret, $f)
+    }};
+    ((scan_collect $init:expr, $f:expr), $ret:expr) => {
+        $crate::__private::scan_collect($ret, $init, $f)
+    };
+    ((timed $label:expr, $fun:expr), $ret:expr) => {{
+        let value = $ret;
+        let start = ::std::time::Instant::now();
+        let value = ($fun)(value);
+        ::std::eprintln!(
+            "[{}:{}:{}] {}: {:?}",
+            ::core::file!(),
+            ::core::line!(),
+            ::core::column!(),
+            $label,
+            start.elapsed()
+        );
+        value
+    }};
+    ((ceil), $ret:expr) => {
+        $ret.ceil()
+    };
+    ((floor), $ret:expr) => {
+        $ret.floor()
+    };
+    ((round), $ret:expr) => {
+        $ret.round()
+    };
+    ((sqrt), $ret:expr) => {
+        $ret.sqrt()
+    };
+    ((ln), $ret:expr) => {
+        $ret.ln()
+    };
+    ((exp), $ret:expr) => {
+        $ret.exp()
+    };
+    ((to_lowercase), $ret:expr) => {
+        $ret.to_lowercase()
+    };
+    ((to_uppercase), $ret:expr) => {
+        $ret.to_uppercase()
+    };
+    ((send $tx:expr), $ret:expr) => {{
+        let value = $ret;
+        let _ = $tx.send(::core::clone::Clone::clone(&value));
+        value
+    }};
+    ((flatten_errors), $ret:expr) => {
+        $ret.map_err(|err| {
+            ::std::boxed::Box::new(err) as ::std::boxed::Box<dyn ::std::error::Error>
+        })
+    };
+}
+
+/// Internal
+#[cfg(not(feature = "std"))]
+#[macro_export]
+macro_rules! __internal_pike_std {
+    (($op:ident $($arg:tt)*), $ret:expr) => {
+        ::core::compile_error!(::core::concat!(
+            "`(",
+            ::core::stringify!($op),
+            ")` requires the `std` feature of pike"
+        ))
+    };
+}
+
+/// Internal
+#[cfg(feature = "futures")]
+#[macro_export]
+macro_rules! __internal_pike_futures {
+    ((join_all), $ret:expr) => {
+        $crate::__private::JoinAll::join_all($ret).await
+    };
+}
+
+/// Internal
+#[cfg(not(feature = "futures"))]
+#[macro_export]
+macro_rules! __internal_pike_futures {
+    (($op:ident $($arg:tt)*), $ret:expr) => {
+        ::core::compile_error!(::core::concat!(
+            "`(",
+            ::core::stringify!($op),
+            ")` requires the `futures` feature of pike"
+        ))
+    };
+}
+
+/// Internal
+#[cfg(feature = "tokio")]
+#[macro_export]
+macro_rules! __internal_pike_tokio {
+    ((timeout $duration:expr), $ret:expr) => {
+        $crate::__private::timeout($duration, $ret).await
+    };
+    ((retry_with $backoff:expr, $retries:expr, $fun:expr), $ret:expr) => {
+        $crate::__private::retry_with($ret, $backoff, $retries, $fun).await
+    };
+}
+
+/// Internal
+#[cfg(not(feature = "tokio"))]
+#[macro_export]
+macro_rules! __internal_pike_tokio {
+    (($op:ident $($arg:tt)*), $ret:expr) => {
+        ::core::compile_error!(::core::concat!(
+            "`(",
+            ::core::stringify!($op),
+            ")` requires the `tokio` feature of pike"
+        ))
+    };
+}
+
+/// Internal
+#[macro_export]
+macro_rules! __internal_pike_let_else {
+    ([$($pat:tt)+] [else { $($else:tt)* } => $out:expr], $ret:expr) => {
+        {
+            let $($pat)+ = $ret else { $($else)* };
+            $out
+        }
+    };
+    ([$($pat:tt)+] [else $else:expr => $out:expr], $ret:expr) => {
+        {
+            let $($pat)+ = $ret else { $else };
+            $out
+        }
+    };
+    ([$($pat:tt)*] [$next:tt $($rest:tt)*], $ret:expr) => {
+        $crate::__internal_pike_let_else!([$($pat)* $next] [$($rest)*], $ret)
+    };
+}
+
+/// Internal
+#[macro_export]
+macro_rules! __internal_pike_macro {
+    (@call $fun:ident, paren, [$($arg:tt)*]) => {
+        $fun!($($arg)*)
+    };
+    (@call $fun:ident, bracket, [$($arg:tt)*]) => {
+        $fun![$($arg)*]
+    };
+    (@call $fun:ident, brace, [$($arg:tt)*]) => {
+        $fun!{$($arg)*}
+    };
+    ($fun:ident, $delim:ident, [$($before:tt)*] [_ $($after:tt)*], $ret:expr) => {
+        $crate::__internal_pike_macro!(@call $fun, $delim, [$($before)* $ret $($after)*])
+    };
+    ($fun:ident, $delim:ident, [$($before:tt)*] [$next:tt $($after:tt)*], $ret:expr) => {
+        $crate::__internal_pike_macro!($fun, $delim, [$($before)* $next] [$($after)*], $ret)
+    };
+    ($fun:ident, $delim:ident, [] [], $ret:expr) => {
+        $crate::__internal_pike_macro!(@call $fun, $delim, [$ret])
+    };
+    ($fun:ident, $delim:ident, [$($arg:tt)+] [], $ret:expr) => {
+        $crate::__internal_pike_macro!(@call $fun, $delim, [$ret, $($arg)+])
+    };
+}
+
+/// Validation hook for the `(validate)` operator.
+///
+/// Implement it for your own types to check their invariants inline in a pipeline. `validate`
+/// takes the value by value and hands it back if it's valid, so it fits `pike_res` stages, where
+/// an invalid value ends the pipeline with its error.
+/// ```rust
+/// use pike::{pike_res, PikeValidate};
+/// struct Port(u32);
+///
+/// impl PikeValidate for Port {
+///     type Error = String;
+///
+///     fn validate(self) -> Result<Self, String> {
+///         if self.0 <= 65535 {
+///             Ok(self)
+///         } else {
+///             Err(format!("port {} is out of range", self.0))
+///         }
+///     }
+/// }
+///
+/// let port = Port(70000);
+/// let port = pike_res!(port |> (validate));
+/// assert_eq!(port.err().unwrap(), "port 70000 is out of range");
+/// ```
+pub trait PikeValidate: Sized {
+    /// The error produced for an invalid value.
+    type Error;
+
+    /// Returns the value if it's valid, or an error describing why it isn't.
+    fn validate(self) -> Result<Self, Self::Error>;
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use core::fmt::Display;
+
+    #[cfg(feature = "std")]
+    pub type MemoCache =
+        std::collections::HashMap<core::any::TypeId, std::boxed::Box<dyn core::any::Any>>;
+
+    #[cfg(feature = "std")]
+    pub fn memoize<K, V, F>(
+        cache: &'static std::thread::LocalKey<core::cell::RefCell<MemoCache>>,
+        key: K,
+        f: F,
+    ) -> V
+    where
+        K: Eq + core::hash::Hash + Clone + 'static,
+        V: Clone + 'static,
+        F: FnOnce(K) -> V + 'static,
+    {
+        type Map<K, V> = std::collections::HashMap<K, V>;
+        // A call site inside a generic function is shared by all of its instantiations, so the
+        // type of `f` is part of the key to keep their results apart.
+        let id = core::any::TypeId::of::<(K, V, F)>();
+        let cached = cache.with(|cache| {
+            let cache = cache.borrow();
+            let map = cache.get(&id)?.downcast_ref::<Map<K, V>>()?;
+            map.get(&key).cloned()
+        });
+        if let Some(value) = cached {
+            return value;
+        }
+        // The cache isn't borrowed while `f` runs, so it can use the same stage recursively.
+        let value = f(key.clone());
+        cache.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            let map = cache
+                .entry(id)
+                .or_insert_with(|| std::boxed::Box::new(Map::<K, V>::new()));
+            if let Some(map) = map.downcast_mut::<Map<K, V>>() {
+                map.insert(key, value.clone());
+            }
+        });
+        value
+    }
+
+    pub trait ExpectWith<T, F> {
+        fn expect_with(self, f: F) -> T;
+    }
+
+    impl<T, E, M: Display, F: FnOnce(E) -> M> ExpectWith<T, F> for Result<T, E> {
+        #[track_caller]
+        fn expect_with(self, f: F) -> T {
+            match self {
+                Ok(value) => value,
+                Err(err) => panic!("{}", f(err)),
+            }
+        }
+    }
+
+    impl<T, M: Display, F: FnOnce() -> M> ExpectWith<T, F> for Option<T> {
+        #[track_caller]
+        fn expect_with(self, f: F) -> T {
+            match self {
+                Some(value) => value,
+                None => panic!("{}", f()),
+            }
+        }
+    }
+
+    pub fn tap_mut<T>(mut value: T, f: impl FnOnce(&mut T)) -> T {
+        f(&mut value);
+        value
+    }
+
+    #[track_caller]
+    pub fn satisfies<T>(value: T, pred: impl FnOnce(&T) -> bool, stage: &str) -> T {
+        if !pred(&value) {
+            panic!("pipeline value does not satisfy `(satisfies {})`", stage);
+        }
+        value
+    }
+
+    #[cfg(feature = "futures")]
+    pub trait JoinAll {
+        type Future: core::future::Future;
+
+        fn join_all(self) -> Self::Future;
+    }
+
+    #[cfg(feature = "futures")]
+    macro_rules! impl_join_all {
+        ($join:ident, $join_fn:ident, $($fut:ident),+) => {
+            impl<$($fut: core::future::Future),+> JoinAll for ($($fut,)+) {
+                type Future = futures::future::$join<$($fut),+>;
+
+                #[allow(non_snake_case)]
+                fn join_all(self) -> Self::Future {
+                    let ($($fut,)+) = self;
+                    futures::future::$join_fn($($fut),+)
+                }
+            }
+        };
+    }
+
+    #[cfg(feature = "futures")]
+    impl_join_all!(Join, join, A, B);
+    #[cfg(feature = "futures")]
+    impl_join_all!(Join3, join3, A, B, C);
+    #[cfg(feature = "futures")]
+    impl_join_all!(Join4, join4, A, B, C, D);
+    #[cfg(feature = "futures")]
+    impl_join_all!(Join5, join5, A, B, C, D, E);
+
+    #[cfg(feature = "tokio")]
+    pub fn timeout<F: core::future::IntoFuture>(
+        duration: core::time::Duration,
+        future: F,
+    ) -> tokio::time::Timeout<F::IntoFuture> {
+        tokio::time::timeout(duration, future)
+    }
+
+    #[cfg(feature = "tokio")]
+    pub async fn retry_with<T: Clone, F: core::future::IntoFuture<Output = Result<U, E>>, U, E>(
+        value: T,
+        backoff: core::time::Duration,
+        mut retries: usize,
+        mut fun: impl FnMut(T) -> F,
+    ) -> Result<U, E> {
+        loop {
+            match fun(value.clone()).await {
+                Err(_) if retries > 0 => {
+                    retries -= 1;
+                    tokio::time::sleep(backoff).await;
+                }
+                ret => return ret,
+            }
+        }
+    }
+
+    pub fn guard<T, E>(
+        value: T,
+        pred: impl FnOnce(&T) -> bool,
+        err: impl FnOnce() -> E,
+    ) -> Result<T, E> {
+        if pred(&value) {
+            Ok(value)
+        } else {
+            Err(err())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn scan_collect<I: IntoIterator, S: Clone>(
+        iter: I,
+        init: S,
+        mut f: impl FnMut(S, I::Item) -> S,
+    ) -> std::vec::Vec<S> {
+        iter.into_iter()
+            .scan(init, |state, item| {
+                *state = f(state.clone(), item);
+                Some(state.clone())
+            })
+            .collect()
+    }
+
+    pub fn holds<T>(value: &T, pred: impl FnOnce(&T) -> bool) -> bool {
+        pred(value)
+    }
+
+    pub fn tap_if<T>(value: T, pred: impl FnOnce(&T) -> bool, f: impl FnOnce(&T)) -> T {
+        if pred(&value) {
+            f(&value);
+        }
+        value
+    }
+
+    pub fn zip_with<A: Iterator, B: IntoIterator, R>(
+        iter: A,
+        other: B,
+        mut f: impl FnMut(A::Item, B::Item) -> R,
+    ) -> impl Iterator<Item = R> {
+        iter.zip(other).map(move |(a, b)| f(a, b))
+    }
+}
+
+#[cfg(test)]
+mod test_pike_opt {
+    fn times2(a: u32) -> Option<u32> {
+        Some(a * 2)
+    }
+
+    fn nope(_a: u32) -> Option<u32> {
+        None
+    }
+
+    #[test]
+    fn accepts_options() {
+        let ret = pike_opt!(4 |> times2);
+
+        assert_eq!(ret, Some(8));
+    }
+
+    #[test]
+    fn accepts_unwrap() {
+        let ret = pike_opt!(4 |> times2).unwrap();
+
+        assert_eq!(ret, 8);
+    }
+
+    #[test]
+    fn exits_early() {
+        let ret = pike_opt! {
+            4
+            |> times2
+            |> times2
+            |> times2
+        };
+
+        assert_eq!(ret, Some(8));
+    }
+
+    #[test]
+    fn goes_until_some() {
+        let ret = pike_opt! {
+            4
+            |> nope
+            |> nope
+            |> (|_i: u32| None)
+            |> times2
+            |> nope
+        };
+
+        assert_eq!(ret, Some(8));
+    }
+
+    #[test]
+    fn ends_with_none() {
+        let ret = pike_opt! {
+            4
+            |> nope
+            |> nope
+            |> (|_i| None)
+            |> nope
+        };
+
+        assert_eq!(ret, None);
+    }
+
+    #[test]
+    fn falls_back_on_overflow() {
+        let max = u32::MAX;
+        let ret = pike_opt!(max |> (checked_add 1) |> (checked_sub 1));
+
+        assert_eq!(ret, Some(u32::MAX - 1));
+
+        let ret = pike_opt!(4u32 |> (checked_add 1) |> (checked_sub 1));
+
+        assert_eq!(ret, Some(5));
+    }
+
+    #[test]
+    fn accepts_expression_heads() {
+        let ret = pike_opt!((2 + 2) |> nope |> times2);
+
+        assert_eq!(ret, Some(8));
+    }
+}
+
+#[cfg(test)]
+mod test_pike_res {
+    fn times2(a: u32) -> Result<u32, String> {
+        Ok(a * 2)
+    }
+
+    fn fail_if_over_4(a: u32) -> Result<u32, String> {
+        if a > 4 {
+            return Err("This number is larger than four".to_string());
+        }
+        Ok(a)
+    }
+
+    #[test]
+    fn accepts_results() {
+        let ret = pike_res!(4 |> times2);
+
+        assert_eq!(ret, Ok(8));
+    }
+
+    #[test]
+    fn accepts_unwrap() {
+        let ret = pike_res!(4 |> times2).unwrap();
+
+        assert_eq!(ret, 8);
+    }
+
+    #[test]
+    fn chains_result_values() {
+        let ret = pike_res! {
+            4
+            |> times2
+            |> times2
+            |> times2
+        };
+
+        assert_eq!(ret, Ok(32));
+    }
+
+    #[test]
+    fn exits_early() {
+        let ret = pike_res! {
+            4
+            |> times2
+            |> fail_if_over_4
+            |> times2
+            |> times2
+        };
+
+        assert_eq!(ret, Err("This number is larger than four".to_string()));
+    }
+
+    #[test]
+    fn runs_try_blocks() {
+        let ret = pike_res! {
+            4u32
+            |> (try_block it => {
+                let n = it.checked_add(1).ok_or("overflow")?;
+                Ok(n * 2)
+            })
+            |> times2
+        };
+
+        assert_eq!(ret, Ok(20));
+
+        let max = u32::MAX;
+        let ret = pike_res! {
+            max
+            |> (try_block it => {
+                let n = it.checked_add(1).ok_or("overflow")?;
+                Ok(n * 2)
+            })
+            |> times2
+        };
+
+        assert_eq!(ret, Err("overflow".to_string()));
+
+        let unit = String::from("px");
+        let ret: Result<String, String> = pike! {
+            "4"
+            |> (try_block it => {
+                let n: u32 = it.parse().map_err(|_| format!("not a number of {unit}"))?;
+                Ok(format!("{n}{unit}"))
+            })
+        };
+
+        assert_eq!(ret, Ok("4px".to_string()));
+        assert_eq!(unit, "px");
+    }
+
+    #[test]
+    fn guards_values() {
+        let ret = pike_res! {
+            2
+            |> times2
+            |> (guard |i| *i <= 4, "too big".to_string())
+            |> times2
+        };
+
+        assert_eq!(ret, Ok(8));
+
+        let ret = pike_res! {
+            4
+            |> times2
+            |> (guard |i| *i <= 4, "too big".to_string())
+            |> times2
+        };
+
+        assert_eq!(ret, Err("too big".to_string()));
+    }
+
+    #[test]
+    fn validates_utf8() {
+        fn first_word(s: &str) -> Result<&str, core::str::Utf8Error> {
+            Ok(s.split(' ').next().unwrap_or_default())
+        }
+
+        let bytes: &[u8] = b"hello world";
+        let ret = pike!(bytes |> (from_utf8) |> (into_res { |> first_word |> first_word }));
+
+        assert_eq!(ret, Ok("hello"));
+
+        let mut owned = b"hi".to_vec();
+        owned[1] = 0xff;
+        let bytes = owned.as_slice();
+        let ret = pike!(bytes |> (from_utf8) |> (into_res { |> first_word }));
+
+        assert_eq!(ret.unwrap_err().valid_up_to(), 1);
+    }
+
+    #[test]
+    fn validates_values() {
+        use crate::PikeValidate;
+
+        #[derive(Debug, PartialEq)]
+        struct Rgb(u32, u32, u32);
+
+        impl PikeValidate for Rgb {
+            type Error = String;
+
+            fn validate(self) -> Result<Self, String> {
+                match [self.0, self.1, self.2].into_iter().find(|c| *c > 255) {
+                    Some(c) => Err(format!("{c} is out of range")),
+                    None => Ok(self),
+                }
+            }
+        }
+
+        let brighten = |c: Rgb| Ok(Rgb(c.0 + 100, c.1 + 100, c.2 + 100));
+
+        let color = Rgb(10, 20, 30);
+        let ret = pike_res!(color |> (validate) |> brighten |> (validate));
+
+        assert_eq!(ret, Ok(Rgb(110, 120, 130)));
+
+        let color = Rgb(10, 200, 30);
+        let ret = pike_res!(color |> (validate) |> brighten |> (validate) |> brighten);
+
+        assert_eq!(ret, Err("300 is out of range".to_string()));
+    }
+
+    #[test]
+    fn bridges_options() {
+        fn first_digit(text: &str) -> Option<u32> {
+            text.chars().find_map(|c| c.to_digit(10))
+        }
+
+        let not_found = || "no digit".to_string();
+        let ret = pike! {
+            "a2"
+            |> first_digit
+            |> (into_result not_found())
+            |> (into_res { |> times2 |> fail_if_over_4 })
+        };
+
+        assert_eq!(ret, Ok(4));
+
+        let ret = pike! {
+            "ab"
+            |> first_digit
+            |> (into_result not_found())
+            |> (into_res { |> times2 |> fail_if_over_4 })
+        };
+
+        assert_eq!(ret, Err("no digit".to_string()));
+    }
+
+    #[test]
+    fn runs_nested_pipelines() {
+        fn parse(text: &str) -> Result<u32, String> {
+            text.parse().map_err(|_| "not a number".to_string())
+        }
+
+        let ret = pike!("2" |> parse |> (into_res { |> times2 |> fail_if_over_4 |> times2 }));
+
+        assert_eq!(ret, Ok(8));
+
+        let ret = pike!("4" |> parse |> (into_res { |> times2 |> fail_if_over_4 |> times2 }));
+
+        assert_eq!(ret, Err("This number is larger than four".to_string()));
+
+        let ret = pike!("four" |> parse |> (into_res { |> times2 }));
+
+        assert_eq!(ret, Err("not a number".to_string()));
+    }
+
+    #[test]
+    fn accepts_expression_heads() {
+        let ret = pike_res!((2 + 2) |> times2 |> fail_if_over_4);
+
+        assert_eq!(ret, Err("This number is larger than four".to_string()));
+    }
+}
+
+#[cfg(test)]
+mod test_pike_ctx {
+    struct Config {
+        factor: u32,
+        label: String,
+    }
+
+    fn scale(a: u32, config: &Config) -> u32 {
+        a * config.factor
+    }
+
+    fn scale_and_add(a: u32, config: &Config, b: u32) -> u32 {
+        a * config.factor + b
+    }
+
+    fn times2(a: u32) -> u32 {
+        a * 2
+    }
+
+    #[test]
+    fn threads_context() {
+        let config = Config {
+            factor: 10,
+            label: "total".to_string(),
+        };
+        let ret = pike_ctx! {
+            config;
+            2
+            |> (with_ctx scale)
+            |> times2
+            |> (with_ctx scale_and_add(5))
+            |> (with_ctx |n: u32, config: &Config| format!("{}: {}", config.label, n))
+        };
+
+        assert_eq!(ret, "total: 405");
+    }
+
+    #[test]
+    fn context_is_borrowed() {
+        let config = Config {
+            factor: 3,
+            label: "unused".to_string(),
+        };
+        let ret = pike_ctx!(config; 2 |> (with_ctx scale) |> (with_ctx scale));
+
+        assert_eq!(ret, 18);
+        assert_eq!(config.factor, 3);
+    }
+
+    #[test]
+    fn accepts_expression_heads() {
+        let config = Config {
+            factor: 3,
+            label: "unused".to_string(),
+        };
+        let ret = pike_ctx!(config; (2 + 2) |> (with_ctx scale));
+
+        assert_eq!(ret, 12);
+    }
+
+    #[test]
+    fn nested_pipelines_borrow_context() {
+        let config = Config {
+            factor: 3,
+            label: "big".to_string(),
+        };
+        let ret = pike_ctx! {
+            config;
+            4
+            |> (with_ctx scale)
+            |> (cond |n| *n > 10 => { |> (|n| scale(n, &config)) } else { |> times2 })
+        };
+
+        assert_eq!(ret, 36);
+    }
+}
+
+#[cfg(test)]
+mod test_pike_pair {
+    fn times2(a: u32) -> u32 {
+        a * 2
+    }
+
+    #[test]
+    fn keeps_the_head() {
+        let ret = pike_pair!(4 |> times2 |> times2);
+
+        assert_eq!(ret, (4, 16));
+    }
+
+    #[test]
+    fn clones_the_head() {
+        let (original, ret) = pike_pair! {
+            (vec![3, 1, 2])
+            |> (tap_mut |v: &mut Vec<u32>| v.sort())
+            |> (into_iter)
+            |> (.map(times2))
+            |> (collect::<Vec<_>>)
+        };
+
+        assert_eq!(original, [3, 1, 2]);
+        assert_eq!(ret, [2, 4, 6]);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test_pike_bench {
+    fn times2(a: u32) -> u32 {
+        a * 2
+    }
+
+    #[test]
+    fn returns_last_result() {
+        let ret = pike_bench!(10, 4 |> times2 |> (|i: u32| i + 1) |> times2);
+
+        assert_eq!(ret, 18);
+    }
+
+    #[test]
+    fn runs_every_iteration() {
+        let mut runs = 0;
+        let ret = pike_bench! {
+            5,
+            runs
+            |> (|i: u32| {
+                runs += 1;
+                i
+            })
+        };
+
+        assert_eq!(ret, 4);
+        assert_eq!(runs, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "pike_bench needs at least one iteration")]
+    fn needs_iterations() {
+        pike_bench!(0, 4 |> times2);
+    }
+
+    #[test]
+    fn accepts_expression_heads() {
+        let seed = vec![1, 2];
+        let ret = pike_bench!(3, (seed.clone()) |> (.len()));
+
+        assert_eq!(ret, 2);
+        assert_eq!(seed, [1, 2]);
+    }
+}
+
+#[cfg(all(test, feature = "futures"))]
+mod test_pike_futures {
+    use futures::channel::oneshot;
+    use futures::executor::block_on;
+
+    async fn times2(a: u32) -> u32 {
+        a * 2
+    }
+
+    #[test]
+    fn joins_futures() {
+        let ret = block_on(async { pike!((times2(1), times2(2), times2(3)) |> (join_all)) });
+
+        assert_eq!(ret, (2, 4, 6));
+    }
+
+    #[test]
+    fn runs_concurrently() {
+        let (tx, rx) = oneshot::channel();
+        let receive = async { rx.await.unwrap() };
+        let send = async { tx.send(4).unwrap() };
+        let ret = block_on(async {
+            pike! {
+                (receive, send)
+                |> (join_all)
+                |> (|(received, ()): (u32, ())| received)
+            }
+        });
+
+        assert_eq!(ret, 4);
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod test_pike_tokio {
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    async fn times2(a: u32) -> u32 {
+        a * 2
+    }
+
+    async fn slow_times2(a: u32) -> u32 {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        a * 2
+    }
+
+    #[tokio::test]
+    async fn finishes_in_time() {
+        let ret = pike!((times2(2)) |> (timeout Duration::from_secs(5)));
+
+        assert_eq!(ret, Ok(4));
+    }
+
+    #[tokio::test]
+    async fn times_out() {
+        let ret = pike! {
+            (slow_times2(2))
+            |> (timeout Duration::from_millis(10))
+            |> (.unwrap_or(0))
+        };
+
+        assert_eq!(ret, 0);
+    }
+
+    #[tokio::test]
+    async fn retries_until_success() {
+        let attempts = Cell::new(0);
+        let flaky_times2 = |a: u32| {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move {
+                if attempt < 3 {
+                    Err("busy")
+                } else {
+                    Ok(a * 2)
+                }
+            }
+        };
+        let ret = pike!(2 |> (retry_with Duration::from_millis(1), 5, flaky_times2));
+
+        assert_eq!(ret, Ok(4));
+        assert_eq!(attempts.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_retries() {
+        let attempts = Cell::new(0);
+        let failing = |_: u32| {
+            attempts.set(attempts.get() + 1);
+            async { Err::<u32, _>("down") }
+        };
+        let ret = pike!(2 |> (retry_with Duration::from_millis(1), 2, failing));
+
+        assert_eq!(ret, Err("down"));
+        assert_eq!(attempts.get(), 3);
+    }
+}
+
+#[cfg(test)]
+mod test_pike_zip {
+    fn sum3(a: u32, b: u32, c: u32) -> u32 {
+        a + b + c
+    }
+
+    fn times2(a: u32) -> u32 {
+        a * 2
+    }
+
+    #[test]
+    fn spreads_head() {
+        let ret = pike_zip!((1, 2, 3) |> sum3);
+
+        assert_eq!(ret, 6);
+    }
+
+    #[test]
+    fn continues_pipeline() {
+        let a = 1;
+        let ret = pike_zip! {
+            (a, a + 1, a + 2)
+            |> sum3
+            |> times2
+            |> (|i: u32| i + 1)
+        };
+
+        assert_eq!(ret, 13);
+    }
+
+    #[test]
+    fn spreads_into_closures_and_partial_calls() {
+        let ret = pike_zip!((2, "ab") |> (|n: usize, s: &str| s.repeat(n)) |> (.len()));
+
+        assert_eq!(ret, 4);
+
+        let ret = pike_zip!((1, 2) |> (sum3(3)));
+
+        assert_eq!(ret, 6);
+    }
+}
+
+#[cfg(test)]
+mod test_pike {
+    fn times2(a: u32) -> u32 {
+        a * 2
+    }
+
+    fn times(a: u32, b: u32, c: u32) -> u32 {
+        a * b * c
+    }
+
+    #[test]
+    fn test_int() {
+        let multiply = |i: u32| i * 2;
+        let ret = pike! {
+            4
+            |> times2
+            |> (|i: u32| i * 2)
+            |> multiply
+            |> (times(100, 10))
+        };
+
+        assert_eq!(ret, 32000);
+    }
+
+    #[test]
+    fn test_string() {
+        let ret = pike! {
+            "abcd"
+            |> str::len
+            |> (as u32)
+            |> times2
+            |> (times(100, 10))
+            |> &
+            |> u32::to_string
+        };
+
+        //let ret = "abcd";
+        //let ret = ret.len();
+        //let ret = ret as u32;
+        //let ret = times2(ret);
+        //let ret = times(ret, 100, 10);
+        //let ret = ret.to_string();
+
+        assert_eq!(ret, times(times2("abcd".len() as u32), 100, 10).to_string());
+        assert_eq!(ret, "8000");
+    }
+
+    #[test]
+    fn test_macro() {
+        let mut ret;
+        macro_rules! test_macro_a {
+            ($it:expr) => {
+                ret = Some($it)
+            };
+        }
+
+        macro_rules! test_macro_b {
+            ($it:expr, $arg:expr) => {
+                ret = Some($it * $arg)
+            };
+        }
+        let multiply = |i: u32| i * 2;
+        pike! {
+            4
+            |> times2
+            |> (|i: u32| i * 2)
             |> multiply
             |> test_macro_a!
         };
 
-        assert_eq!(ret, Some(32));
+        assert_eq!(ret, Some(32));
+
+        pike! {
+            4
+            |> times2
+            |> (|i: u32| i * 2)
+            |> multiply
+            |> (test_macro_b!(10))
+        };
+
+        assert_eq!(ret, Some(320));
+    }
+
+    #[test]
+    fn test_replace() {
+        use std::rc::Rc;
+
+        let rc = Rc::new(4);
+        let clone = Rc::clone(&rc);
+        let ret = pike! {
+            clone
+            |> (replace Rc::strong_count(&rc))
+            |> (as u32)
+            |> times2
+        };
+
+        assert_eq!(ret, 2);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_replace_str() {
+        let ret = pike!("a.b.c" |> (replace ".", "::"));
+
+        assert_eq!(ret, "a::b::c");
+
+        let text = String::from("pike pike");
+        let ret = pike!(text |> (replace 'p', "b") |> (replace "ke", "ke!"));
+
+        assert_eq!(ret, "bike! bike!");
+
+        let text = String::from("pike");
+        let pair = (1, 2);
+        let ret = pike!(text |> (replace pair));
+
+        assert_eq!(ret, (1, 2));
+    }
+
+    #[test]
+    fn test_equality() {
+        assert!(pike!(4 |> times2 |> (eq 8)));
+        assert!(pike!(4 |> times2 |> (ne 4)));
+
+        let name = String::from("pike");
+        assert!(pike!(name |> & |> String::len |> (eq 4)));
+        let name = String::from("pike");
+        assert!(pike!(name |> (eq "pike")));
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(pike!(4 |> times2 |> (gt 3)));
+        assert!(pike!(4 |> times2 |> (ge 8)));
+        assert!(pike!(4 |> times2 |> (lt 10)));
+        assert!(pike!(4 |> times2 |> (le 8)));
+        assert!(!pike!(4 |> times2 |> (lt 8)));
+
+        let word = String::from("abc");
+        assert!(pike!(word |> (lt String::from("abd"))));
+    }
+
+    #[test]
+    fn test_take_skip() {
+        let range = 0..10;
+        let ret = pike! {
+            range
+            |> (skip 2)
+            |> (take 3)
+            |> (collect::<Vec<_>>)
+        };
+
+        assert_eq!(ret, vec![2, 3, 4]);
+
+        let numbers = vec![1, 2, 3];
+        let numbers = numbers.as_slice();
+        let ret: Vec<&u32> = pike!(numbers |> (iter) |> (skip 1) |> (collect));
+
+        assert_eq!(ret, vec![&2, &3]);
+    }
+
+    #[test]
+    fn test_qualified_path() {
+        trait Halve {
+            fn halve(self) -> u32;
+            fn halve_and_add(self, n: u32) -> u32;
+        }
+
+        impl Halve for u32 {
+            fn halve(self) -> u32 {
+                self / 2
+            }
+
+            fn halve_and_add(self, n: u32) -> u32 {
+                self / 2 + n
+            }
+        }
+
+        let ret = pike! {
+            4
+            |> times2
+            |> (<u32 as Halve>::halve)
+            |> (<u32 as Halve>::halve_and_add(10))
+            |> &
+            |> (<u32 as ToString>::to_string())
+        };
+
+        assert_eq!(ret, "12");
+    }
+
+    #[test]
+    fn test_flatten() {
+        let nested = Some(Some(4));
+        assert_eq!(pike!(nested |> (flatten)), Some(4));
+
+        let nested: Option<Option<u32>> = Some(None);
+        assert_eq!(pike!(nested |> (flatten)), None);
+
+        let rows = vec![vec![1, 2], vec![], vec![3, 4]];
+        let ret = pike! {
+            rows
+            |> (into_iter)
+            |> (flatten)
+            |> (collect::<Vec<_>>)
+        };
+
+        assert_eq!(ret, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_boxed_closure() {
+        let boxed: Box<dyn Fn(u32) -> u32> = Box::new(|i| i + 1);
+        let boxed_once: Box<dyn FnOnce(u32) -> String> = Box::new(|i| i.to_string());
+        let ret = pike! {
+            4
+            |> boxed
+            |> (boxed)
+            |> times2
+            |> boxed_once
+        };
+
+        assert_eq!(ret, "12");
+    }
+
+    #[test]
+    fn test_sort() {
+        let numbers = vec![4, 1, 3, 2];
+        let ret = pike!(numbers |> (sort));
+
+        assert_eq!(ret, vec![1, 2, 3, 4]);
+
+        let numbers = [3, 1, 2];
+        let ret = pike!(numbers |> (sort) |> (into_iter) |> (take 2) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, vec![1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_sorted() {
+        let ret = pike!("dbca" |> str::chars |> (sorted));
+
+        assert_eq!(ret, vec!['a', 'b', 'c', 'd']);
+    }
+
+    #[test]
+    fn test_trailing_closure() {
+        fn with_lock<T>(lock: &std::sync::Mutex<u32>, f: impl FnOnce(&mut u32) -> T) -> T {
+            f(&mut lock.lock().unwrap())
+        }
+
+        fn or_else_with(a: Option<u32>, f: impl FnOnce() -> u32) -> u32 {
+            a.unwrap_or_else(f)
+        }
+
+        let lock = std::sync::Mutex::new(4);
+        let lock_ref = &lock;
+        let ret = pike! {
+            lock_ref
+            |> (with_lock |guard| {
+                *guard = times2(*guard);
+                *guard
+            })
+        };
+
+        assert_eq!(ret, 8);
+        assert_eq!(*lock.lock().unwrap(), 8);
+
+        fn call_with(a: u32, f: impl FnOnce(u32) -> u32) -> u32 {
+            f(a)
+        }
+
+        let offset = 10;
+        let ret = pike!(None |> (or_else_with || 5) |> (call_with move |n| n + offset));
+
+        assert_eq!(ret, 15);
+        assert_eq!(pike!(offset |> (move |n: u32| n + 1)), 11);
+    }
+
+    #[test]
+    fn test_windows_chunks() {
+        let numbers = vec![1, 2, 3, 4];
+        let slice = numbers.as_slice();
+        let ret = pike!(slice |> (windows 3) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, vec![&[1, 2, 3][..], &[2, 3, 4][..]]);
+
+        let ret = pike!(slice |> (chunks 3) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, vec![&[1, 2, 3][..], &[4][..]]);
+    }
+
+    #[test]
+    fn test_expect_with() {
+        let ret: u32 = pike!("4" |> str::parse |> (expect_with |e| format!("failed: {e}")));
+
+        assert_eq!(ret, 4);
+
+        let second = "abc".chars().nth(1);
+        let ret = pike!(second |> (expect_with || "empty"));
+
+        assert_eq!(ret, 'b');
+    }
+
+    #[test]
+    #[should_panic(expected = "failed: invalid digit found in string")]
+    fn test_expect_with_panics() {
+        let _: u32 = pike!("four" |> str::parse |> (expect_with |e| format!("failed: {e}")));
+    }
+
+    #[test]
+    #[should_panic(expected = "nothing here")]
+    fn test_expect_with_panics_on_none() {
+        let _: u32 = pike!(None |> (expect_with || "nothing here"));
+    }
+
+    #[test]
+    fn test_macro_placeholder() {
+        let ret = pike!(4 |> times2 |> (vec![_; 3]));
+
+        assert_eq!(ret, vec![8, 8, 8]);
+
+        pike!(4 |> times2 |> (assert!(_ > 0)));
+
+        let ret = pike!(4 |> times2 |> (Some) |> (matches!(_, Some(8 | 16))));
+
+        assert!(ret);
+
+        let ret = pike!(4 |> (|i: u32| (i, i * 2)) |> (matches!(_, (_, 8))));
+
+        assert!(ret);
+
+        let ret = pike!(4 |> (matches!(_, 1..=3)));
+
+        assert!(!ret);
+
+        let ret = pike!(4 |> (format!("{}-{}", _, 2)));
+
+        assert_eq!(ret, "4-2");
+    }
+
+    #[test]
+    fn test_macro_placeholder_evaluates_once() {
+        let mut calls = 0;
+        let mut count = |i: u32| {
+            calls += 1;
+            i
+        };
+        let ret = pike!(4 |> count |> (vec![_; 3]));
+
+        assert_eq!(ret, vec![4, 4, 4]);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_recover_default() {
+        let ret: u32 = pike!("4" |> str::parse |> (recover_default) |> times2);
+
+        assert_eq!(ret, 8);
+
+        let ret: u32 = pike!("four" |> str::parse |> (recover_default) |> times2);
+
+        assert_eq!(ret, 0);
+
+        let ret: String = pike!(None |> (recover_default));
+
+        assert_eq!(ret, "");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_catch_unwind() {
+        fn fail_if_over_4(a: u32) -> u32 {
+            if a > 4 {
+                panic!("This number is larger than four");
+            }
+            a
+        }
+
+        let ret = pike!(2 |> (catch_unwind fail_if_over_4) |> (recover_default));
+
+        assert_eq!(ret, 2);
+
+        let ret = pike!(4 |> times2 |> (catch_unwind fail_if_over_4));
+        let err = ret.unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<&str>(),
+            Some(&"This number is larger than four")
+        );
+    }
+
+    #[test]
+    fn test_cast_complex_types() {
+        let bytes = [1u8, 2, 3, 4];
+        let array_ref = &bytes;
+        let ret = pike!(array_ref |> (as &[u8]) |> (|s: &[u8]| s.len()));
+
+        assert_eq!(ret, 4);
+
+        let ret = pike!(array_ref |> (as *const [u8; 4]) |> (as *const u8));
+
+        assert_eq!(ret, bytes.as_ptr());
+        assert_eq!(unsafe { *ret.add(3) }, 4);
+
+        let ret = pike!(bytes |> (as [u8; 4]));
+
+        assert_eq!(ret, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_counts() {
+        let ret = pike!("abracadabra" |> str::chars |> (counts));
+
+        assert_eq!(ret.len(), 5);
+        assert_eq!(ret[&'a'], 5);
+        assert_eq!(ret[&'b'], 2);
+        assert_eq!(ret[&'d'], 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_group_by() {
+        let range = 1..=6u32;
+        let ret = pike!(range |> (group_by |i: &u32| i % 3));
+
+        assert_eq!(ret.len(), 3);
+        assert_eq!(ret[&0], vec![3, 6]);
+        assert_eq!(ret[&1], vec![1, 4]);
+        assert_eq!(ret[&2], vec![2, 5]);
+
+        let mut calls = 0;
+        let words = ["a", "bb", "cc"];
+        let ret = pike! {
+            words
+            |> (group_by |w: &&str| {
+                calls += 1;
+                w.len()
+            })
+        };
+
+        assert_eq!(ret[&2], ["bb", "cc"]);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_dyn_method() {
+        trait Shape {
+            fn area(&self) -> u32;
+            fn scaled_area(&self, by: u32) -> u32;
+        }
+
+        struct Rect(u32, u32);
+
+        impl Shape for Rect {
+            fn area(&self) -> u32 {
+                self.0 * self.1
+            }
+
+            fn scaled_area(&self, by: u32) -> u32 {
+                self.area() * by
+            }
+        }
+
+        let boxed: Box<dyn Shape> = Box::new(Rect(2, 3));
+        let ret = pike!(boxed |> (dyn Shape::area) |> times2);
+
+        assert_eq!(ret, 12);
+
+        let rect = Rect(1, 2);
+        let shape: &dyn Shape = &rect;
+        let ret = pike!(shape |> (dyn Shape::scaled_area(10)));
+
+        assert_eq!(ret, 20);
+
+        let shared: std::rc::Rc<dyn Shape> = std::rc::Rc::new(Rect(3, 3));
+        let ret = pike!(shared |> (dyn Shape::area()));
+
+        assert_eq!(ret, 9);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_join() {
+        let ret = pike!("a b c" |> str::split_whitespace |> (join ", "));
+
+        assert_eq!(ret, "a, b, c");
+
+        let range = 1..4;
+        let ret = pike!(range |> (Iterator::map(|i: u32| i.to_string())) |> (join "-"));
+
+        assert_eq!(ret, "1-2-3");
+    }
+
+    #[test]
+    fn test_enumerate_rev() {
+        let range = 0..4;
+        let ret = pike!(range |> (rev) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, vec![3, 2, 1, 0]);
+
+        let range = 1..4u32;
+        let ret = pike! {
+            range
+            |> (Iterator::map(times2))
+            |> (enumerate)
+            |> (rev)
+            |> (collect::<Vec<_>>)
+        };
+
+        assert_eq!(ret, vec![(2, 6), (1, 4), (0, 2)]);
+    }
+
+    #[test]
+    fn test_scan() {
+        let range = 1..=5u32;
+        let ret = pike! {
+            range
+            |> (scan 0, |acc, x| {
+                *acc += x;
+                Some(*acc)
+            })
+            |> (collect::<Vec<_>>)
+        };
+
+        assert_eq!(ret, vec![1, 3, 6, 10, 15]);
+
+        let range = 1..=5u32;
+        let ret = pike! {
+            range
+            |> (scan 1, |acc, x| {
+                *acc *= x;
+                (*acc < 10).then_some(*acc)
+            })
+            |> (collect::<Vec<_>>)
+        };
+
+        assert_eq!(ret, vec![1, 2, 6]);
+    }
+
+    #[test]
+    fn test_method_call() {
+        use std::rc::Rc;
+
+        let shared = Rc::new("abcd".to_string());
+        let ret = pike!(shared |> (.len()) |> (as u32) |> times2);
+
+        assert_eq!(ret, 8);
+
+        let boxed = Box::new(vec![1, 2, 3]);
+        let ret = pike!(boxed |> (.contains(&2)));
+
+        assert!(ret);
+
+        let text = "a,b";
+        let double_ref = &&text;
+        let ret = pike!(double_ref |> (.split_once(',')));
+
+        assert_eq!(ret, Some(("a", "b")));
+
+        let ret = pike!("12" |> (.parse::<u32>()) |> (recover_default));
+
+        assert_eq!(ret, 12);
+    }
+
+    #[test]
+    fn test_satisfies() {
+        let ret = pike!(4 |> times2 |> (satisfies |i| *i == 8) |> times2);
+
+        assert_eq!(ret, 16);
+
+        let text = String::from("pike");
+        let ret = pike!(text |> (satisfies |s| s.is_ascii()));
+
+        assert_eq!(ret, "pike");
+    }
+
+    #[test]
+    #[should_panic(expected = "pipeline value does not satisfy `(satisfies |i| *i < 8)`")]
+    fn test_satisfies_panics() {
+        pike!(4 |> times2 |> (satisfies |i| *i < 8));
+    }
+
+    #[test]
+    fn test_flat_map() {
+        let range = 1..4;
+        let ret = pike! {
+            range
+            |> (flat_map |i| std::iter::repeat(i).take(i))
+            |> (collect::<Vec<_>>)
+        };
+
+        assert_eq!(ret, vec![1, 2, 2, 3, 3, 3]);
+
+        let ret =
+            pike!("ab cd" |> str::split_whitespace |> (flat_map str::chars) |> (collect::<String>));
+
+        assert_eq!(ret, "abcd");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_dedup() {
+        let numbers = vec![1, 1, 2, 2, 2, 3, 1];
+        let ret = pike!(numbers |> (dedup));
+
+        assert_eq!(ret, vec![1, 2, 3, 1]);
+
+        let numbers = [4, 4, 8];
+        let slice = &numbers[..];
+        let ret = pike!(slice |> (dedup));
+
+        assert_eq!(ret, vec![4, 8]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_unique() {
+        let ret = pike!("abracadabra" |> str::chars |> (unique));
+
+        assert_eq!(ret, vec!['a', 'b', 'r', 'c', 'd']);
+
+        let words = vec!["a".to_string(), "b".to_string(), "a".to_string()];
+        let ret = pike!(words |> (unique));
+
+        assert_eq!(ret, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_or() {
+        let ret = pike!(None |> (or Some(5)) |> double_some);
+
+        assert_eq!(ret, Some(10));
+
+        let ret = pike!(3 |> (Some) |> (or Some(5)));
+
+        assert_eq!(ret, Some(3));
+
+        let ret: Result<u32, ()> = pike!("four" |> (.parse::<u32>()) |> (or Ok(0)));
+
+        assert_eq!(ret, Ok(0));
+    }
+
+    fn double_some(a: Option<u32>) -> Option<u32> {
+        a.map(times2)
+    }
+
+    #[test]
+    fn test_step_by() {
+        let range = 1..=10;
+        let ret = pike!(range |> (step_by 3) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, vec![1, 4, 7, 10]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_step_by_zero() {
+        let range = 1..=10;
+        let _ = pike!(range |> (step_by 0));
+    }
+
+    #[test]
+    fn test_tap_mut() {
+        let numbers = vec![4, 1, 3];
+        let ret = pike! {
+            numbers
+            |> (tap_mut |v| v.sort())
+            |> (tap_mut |v| v.retain(|i| *i > 1))
+            |> (into_iter)
+            |> (collect::<Vec<_>>)
+        };
+
+        assert_eq!(ret, vec![3, 4]);
+
+        let ret = pike!(4 |> times2 |> (tap_mut |i| *i += 1));
+
+        assert_eq!(ret, 9);
+    }
+
+    #[test]
+    fn test_on_some_on_ok() {
+        let ret = pike!(4 |> (Some) |> (on_some times2) |> (on_some |i| i + 1));
+
+        assert_eq!(ret, Some(9));
+
+        let none: Option<u32> = None;
+        let ret = pike!(none |> (on_some times2));
+
+        assert_eq!(ret, None);
+
+        let ret: Result<u32, std::num::ParseIntError> = pike!("4" |> str::parse |> (on_ok times2));
+
+        assert_eq!(ret, Ok(8));
+
+        let ret: Result<u32, std::num::ParseIntError> =
+            pike!("four" |> str::parse |> (on_ok times2));
+
+        assert!(ret.is_err());
+    }
+
+    #[test]
+    fn test_array_from_fn() {
+        let double = |i: usize| times2(i as u32);
+        let ret: [u32; 4] = pike!(double |> (array_from_fn));
+
+        assert_eq!(ret, [0, 2, 4, 6]);
+
+        let double = |i: usize| times2(i as u32);
+        let ret = pike!(double |> (array_from_fn) |> (|a: [u32; 2]| a.len()));
+
+        assert_eq!(ret, 2);
+    }
+
+    #[test]
+    fn test_tee() {
+        let mut seen = Vec::new();
+        let ret = pike! {
+            4
+            |> times2
+            |> (tee { |> times2 |> (|i: u32| seen.push(i)) })
+            |> (tee { |> (as u64) })
+            |> times2
+        };
+
+        assert_eq!(ret, 16);
+        assert_eq!(seen, vec![16]);
+
+        let text = String::from("pike");
+        let ret = pike!(text |> (tee { |> (tap_mut |s| s.push('!')) |> (assert_eq!(_, "pike!")) }));
+
+        assert_eq!(ret, "pike");
+    }
+
+    #[test]
+    fn test_position() {
+        let range = 1..10;
+        let ret = pike!(range |> (position |x| x == 3));
+
+        assert_eq!(ret, Some(2));
+
+        let numbers = [1, 2, 3];
+        let slice = &numbers[..];
+        let ret = pike!(slice |> (iter) |> (position |x| *x == 4));
+
+        assert_eq!(ret, None);
+    }
+
+    #[test]
+    fn test_find() {
+        let range = 0..5;
+        let ret = pike!(range |> (find |x| *x > 2));
+
+        assert_eq!(ret, Some(3));
+
+        let range = 0..5;
+        let ret = pike!(range |> (find |x| *x > 5));
+
+        assert_eq!(ret, None);
+    }
+
+    #[test]
+    fn test_any_all() {
+        let range = 0..5;
+        assert!(pike!(range |> (any |x| x > 2)));
+
+        let range = 0..5;
+        assert!(!pike!(range |> (any |x| x > 5)));
+
+        let range = 1..5;
+        assert!(pike!(range |> (all |x| x > 0)));
+
+        let range = 0..5;
+        assert!(!pike!(range |> (all |x| x > 0)));
+    }
+
+    #[test]
+    fn test_let_else() {
+        fn halve_even(n: Option<u32>) -> Option<u32> {
+            let ret = pike! {
+                n
+                |> (let_else Some(x @ 0..=100) else return None => x)
+                |> (|x: u32| (x % 2 == 0, x / 2))
+                |> (let_else (true, half) else { return None } => half)
+                |> times2
+            };
+            Some(ret / 2)
+        }
+
+        assert_eq!(halve_even(Some(8)), Some(4));
+        assert_eq!(halve_even(Some(7)), None);
+        assert_eq!(halve_even(Some(200)), None);
+        assert_eq!(halve_even(None), None);
+    }
+
+    #[test]
+    fn test_reduce() {
+        let range = 1..5;
+        let ret = pike!(range |> (reduce |a, b| a * b));
+
+        assert_eq!(ret, Some(24));
+
+        let range = 0..0;
+        let ret = pike!(range |> (reduce |a, b| a * b));
+
+        assert_eq!(ret, None);
+    }
+
+    #[test]
+    fn test_writeln() {
+        use core::fmt::Write;
+
+        let mut buf = String::new();
+        let ret = pike!(7 |> times2 |> (writeln!(buf, "{}", _)));
+
+        assert_eq!(ret, Ok(()));
+
+        let ret = pike!(8 |> (write!(buf, "[{}]", _)));
+
+        assert!(ret.is_ok());
+        assert_eq!(buf, "14\n[8]");
+    }
+
+    #[test]
+    fn test_cycle() {
+        let pattern: &[u32] = &[1, 2];
+        let ret = pike!(pattern |> (iter) |> (cycle) |> (take 5) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, [&1, &2, &1, &2, &1]);
+    }
+
+    #[test]
+    fn test_peekable() {
+        let range = 0..3;
+        let ret = pike!(range |> (peekable) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, [0, 1, 2]);
+
+        fn leading_zero(mut iter: core::iter::Peekable<core::ops::Range<u32>>) -> bool {
+            iter.peek() == Some(&0)
+        }
+
+        let range = 0..3;
+        assert!(pike!(range |> (peekable) |> leading_zero));
+    }
+
+    #[test]
+    fn test_max_min_by_key() {
+        let words = vec!["tree", "a", "branch", "leaf"];
+        let longest = pike!(words |> (into_iter) |> (max_by_key |w: &&str| w.len()));
+
+        assert_eq!(longest, Some("branch"));
+
+        let words = vec!["tree", "a", "branch", "leaf"];
+        let shortest = pike!(words |> (into_iter) |> (min_by_key |w: &&str| w.len()));
+
+        assert_eq!(shortest, Some("a"));
+
+        let words: Vec<&str> = vec![];
+        let none = pike!(words |> (into_iter) |> (max_by_key |w: &&str| w.len()));
+
+        assert_eq!(none, None);
+    }
+
+    #[test]
+    fn test_try_fold() {
+        let numbers = vec![1u32, 2, 3];
+        let ret = pike! {
+            numbers
+            |> (into_iter)
+            |> (try_fold 0u32, |acc, x| acc.checked_add(x).ok_or("overflow"))
+        };
+
+        assert_eq!(ret, Ok(6));
+
+        let numbers = vec![1u32, u32::MAX, 3];
+        let mut seen = 0;
+        let ret = pike! {
+            numbers
+            |> (into_iter)
+            |> (.inspect(|_| seen += 1))
+            |> (try_fold 0u32, |acc, x| acc.checked_add(x).ok_or("overflow"))
+        };
+
+        assert_eq!(ret, Err("overflow"));
+        assert_eq!(seen, 2);
+    }
+
+    #[test]
+    fn test_numeric() {
+        let num = -4i32;
+        assert_eq!(pike!(num |> (abs)), 4);
+        assert_eq!(pike!(num |> (signum)), -1);
+        assert_eq!(pike!(0i64 |> (signum)), 0);
+        assert_eq!(pike!(3u64 |> (pow 3)), 27);
+
+        let num = -2i8;
+        assert_eq!(pike!(num |> (pow 3)), -8);
+
+        let num = -5i32;
+        let exp = 2;
+        assert_eq!(pike!(num |> (abs) |> (pow exp)), 25);
+
+        let num = -1.5f64;
+        assert_eq!(pike!(num |> (abs)), 1.5);
+        assert_eq!(pike!(2.0f32 |> (signum)), 1.0);
+    }
+
+    #[test]
+    fn test_slice_parts() {
+        let numbers: &[u32] = &[5, 6, 7];
+        let (head, tail) = pike!(numbers |> (split_at 2));
+
+        assert_eq!(head, [5, 6]);
+        assert_eq!(tail, [7]);
+
+        assert_eq!(pike!(numbers |> (first)), Some(&5));
+        assert_eq!(pike!(numbers |> (last)), Some(&7));
+
+        let empty: &[u32] = &[];
+        assert_eq!(pike!(empty |> (first)), None);
+        assert_eq!(pike!(empty |> (last)), None);
+    }
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(pike!(4u32 |> (checked_add 1)), Some(5));
+        assert_eq!(pike!(4u32 |> (checked_sub 3)), Some(1));
+        assert_eq!(pike!(4u32 |> (checked_mul 2)), Some(8));
+        assert_eq!(pike!(4u32 |> (checked_div 2)), Some(2));
+
+        let max = u32::MAX;
+        assert_eq!(pike!(max |> (checked_add 1)), None);
+        assert_eq!(pike!(2u32 |> (checked_sub 3)), None);
+        assert_eq!(pike!(max |> (checked_mul 2)), None);
+        assert_eq!(pike!(4u32 |> (checked_div 0)), None);
+    }
+
+    #[test]
+    fn test_saturating_wrapping() {
+        let max = u8::MAX;
+        assert_eq!(pike!(max |> (saturating_add 10)), 255);
+        assert_eq!(pike!(max |> (wrapping_add 10)), 9);
+        assert_eq!(pike!(3u8 |> (saturating_sub 10)), 0);
+        assert_eq!(pike!(3u8 |> (wrapping_sub 10)), 249);
+        assert_eq!(pike!(max |> (saturating_mul 2)), 255);
+        assert_eq!(pike!(max |> (wrapping_mul 2)), 254);
+
+        let min = i8::MIN;
+        assert_eq!(pike!(min |> (saturating_sub 1)), i8::MIN);
+        assert_eq!(pike!(min |> (wrapping_sub 1)), i8::MAX);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_dbg_type() {
+        let ret = pike!(4 |> times2 |> (dbg_type) |> times2);
+
+        assert_eq!(ret, 16);
+
+        let text = String::from("pike");
+        let ret = pike!(text |> (dbg_type) |> (.len()));
+
+        assert_eq!(ret, 4);
+    }
+
+    #[test]
+    fn test_apply() {
+        let transforms: Vec<Box<dyn Fn(u32) -> u32>> = vec![Box::new(times2), Box::new(|i| i + 1)];
+        let chosen = &transforms[1];
+        let ret = pike!(4 |> (apply chosen) |> (apply transforms[0]));
+
+        assert_eq!(ret, 10);
+
+        let pick = |double: bool| -> fn(u32) -> u32 {
+            if double {
+                times2
+            } else {
+                |i| i - 1
+            }
+        };
+        let ret = pike!(4 |> (apply pick(false)) |> (apply pick(true)));
+
+        assert_eq!(ret, 6);
+    }
+
+    #[test]
+    fn test_split() {
+        let csv = "4,8,15";
+        let ret = pike!(csv |> (split ',') |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, ["4", "8", "15"]);
+
+        let owned = String::from("a, b, c");
+        let text = owned.as_str();
+        let ret = pike!(text |> (split ", ") |> (.count()));
+
+        assert_eq!(ret, 3);
+    }
+
+    #[test]
+    fn test_lines_chars_bytes() {
+        let text = "first\r\nsecond\nthird";
+        let ret = pike!(text |> (lines) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, ["first", "second", "third"]);
+
+        let ret = pike!("word" |> (chars) |> (enumerate) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, [(0, 'w'), (1, 'o'), (2, 'r'), (3, 'd')]);
+
+        let ret = pike!("AZ" |> (bytes) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, [b'A', b'Z']);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_trim_case() {
+        let input = String::from("\t MiXeD Case  ");
+        let ret = pike!(input |> (trim) |> (to_lowercase));
+
+        assert_eq!(ret, "mixed case");
+
+        let ret = pike!("  MiXeD " |> (trim) |> (to_uppercase));
+
+        assert_eq!(ret, "MIXED");
+
+        let ret = pike!(" ok " |> (trim));
+
+        assert_eq!(ret, "ok");
+    }
+
+    #[test]
+    fn test_parse() {
+        let ret = pike!("12" |> (parse::<u32>) |> (.unwrap()) |> times2);
+
+        assert_eq!(ret, 24);
+
+        let ret: Result<i64, _> = pike!("-7" |> (parse));
+
+        assert_eq!(ret, Ok(-7));
+
+        let ret = pike!("seven" |> (parse::<u8>));
+
+        assert!(ret.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_send() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let ret = pike!(4 |> (send tx) |> times2 |> (send tx) |> times2);
+
+        assert_eq!(ret, 16);
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), [4, 8]);
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        drop(rx);
+        let ret = pike!(4 |> (send tx) |> times2);
+
+        assert_eq!(ret, 8);
+    }
+
+    #[test]
+    fn test_recover() {
+        let ret = pike!("4" |> (parse::<u32>) |> (recover |_| 0) |> times2);
+
+        assert_eq!(ret, 8);
+
+        let ret = pike! {
+            "four"
+            |> (parse::<u32>)
+            |> (.map_err(|_| 21))
+            |> (recover |e| e)
+            |> times2
+        };
+
+        assert_eq!(ret, 42);
+
+        let ret = pike!(None |> (recover || 3) |> times2);
+
+        assert_eq!(ret, 6);
+    }
+
+    #[test]
+    fn test_zip_with() {
+        let range = 0..4;
+        let ret = pike!(range |> (zip_with 10..20, |a, b| a + b) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, [10, 12, 14, 16]);
+
+        let words = vec!["a", "b"];
+        let range = 1..;
+        let ret = pike!(range |> (zip_with words, |n, w| format!("{w}{n}")) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, ["a1", "b2"]);
+    }
+
+    #[test]
+    fn test_as_bytes_from_utf8() {
+        let text = "héllo";
+        let ret = pike!(text |> (as_bytes) |> (from_utf8));
+
+        assert_eq!(ret, Ok("héllo"));
+
+        let text = "héllo";
+        let ret = pike!(text |> (as_bytes) |> (.len()));
+
+        assert_eq!(ret, 6);
+    }
+
+    #[test]
+    fn test_map_or() {
+        let some = Some(4);
+        let ret = pike!(some |> (map_or 0, times2));
+
+        assert_eq!(ret, 8);
+
+        let ret = pike!(None |> (map_or 1, times2));
+
+        assert_eq!(ret, 1);
+
+        let ret = pike!("4" |> (parse::<u32>) |> (map_or 0, times2));
+
+        assert_eq!(ret, 8);
+
+        let ret = pike!("four" |> (parse::<u32>) |> (map_or 0, times2));
+
+        assert_eq!(ret, 0);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let input: Option<Result<u32, String>> = Some(Ok(4));
+        let ret = pike!(input |> (transpose));
+
+        assert_eq!(ret, Ok(Some(4)));
+
+        let input: Option<Result<u32, String>> = None;
+        let ret = pike!(input |> (transpose));
+
+        assert_eq!(ret, Ok(None));
+
+        let input: Result<Option<u32>, String> = Err("no".to_string());
+        let ret = pike!(input |> (transpose));
+
+        assert_eq!(ret, Some(Err("no".to_string())));
+
+        let input: Result<Option<u32>, String> = Ok(None);
+        let ret = pike!(input |> (transpose));
+
+        assert_eq!(ret, None);
+    }
+
+    #[test]
+    fn test_build() {
+        let ret = pike! {
+            "pike"
+            |> (build .to_uppercase().repeat(2).into_bytes())
+            |> (.len())
+        };
+
+        assert_eq!(ret, 8);
+
+        let command = std::process::Command::new("pike");
+        let ret = pike!(command |> (build .arg("-v").args(["a", "b"]).get_args().count()));
+
+        assert_eq!(ret, 3);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_flatten_errors() {
+        use std::error::Error;
+
+        let ret = pike!("4" |> (parse::<u32>) |> (flatten_errors) |> (.unwrap()));
+
+        assert_eq!(ret, 4);
+
+        let mut owned = b"hi".to_vec();
+        owned[1] = 0xff;
+        let bytes = owned.as_slice();
+        let errors: Vec<Box<dyn Error>> = vec![
+            pike!("four" |> (parse::<u32>) |> (flatten_errors)).unwrap_err(),
+            pike!(bytes |> (from_utf8) |> (flatten_errors)).unwrap_err(),
+        ];
+
+        assert!(errors[0].is::<core::num::ParseIntError>());
+        assert!(errors[1].is::<core::str::Utf8Error>());
+    }
+
+    #[test]
+    fn test_cond() {
+        let even = |i: &u32| i % 2 == 0;
+        let ret = pike!(4 |> (cond even => { |> times2 |> times2 } else { |> (|i| i + 1) }));
+
+        assert_eq!(ret, 16);
+
+        let ret = pike!(5 |> (cond even => { |> times2 |> times2 } else { |> (|i| i + 1) }));
+
+        assert_eq!(ret, 6);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_cond_owned() {
+        let ret = pike! {
+            "pike"
+            |> (cond |s| s.len() > 3 => { |> (to_uppercase) } else { |> (.to_string()) })
+        };
+
+        assert_eq!(ret, "PIKE");
+    }
+
+    #[test]
+    fn test_expression_head() {
+        let ret = pike! {
+            (Vec::with_capacity(16))
+            |> (tap_mut |v: &mut Vec<u32>| v.push(1))
+            |> (tap_mut |v: &mut Vec<u32>| v.push(2))
+        };
+
+        assert_eq!(ret, [1, 2]);
+        assert!(ret.capacity() >= 16);
 
-        pike! {
-            4
+        let ret = pike!((2 + 2) |> times2);
+
+        assert_eq!(ret, 8);
+
+        let ret = pike!((1, 2) |> (|(a, b): (u32, u32)| a + b));
+
+        assert_eq!(ret, 3);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_debug_pretty() {
+        let ret = pike!(4 |> times2 |> (debug_pretty) |> times2);
+
+        assert_eq!(ret, 16);
+
+        let text = "a b";
+        let ret = pike!(text |> (split ' ') |> (collect::<Vec<_>>) |> (debug_pretty));
+
+        assert_eq!(ret, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_chunk_by() {
+        let numbers: &[u32] = &[1, 2, 3, 7, 8, 10];
+        let ret = pike!(numbers |> (chunk_by |a, b| a + 1 == *b) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, [&[1, 2, 3][..], &[7, 8], &[10]]);
+
+        let empty: &[u32] = &[];
+        let ret = pike!(empty |> (chunk_by |a, b| a == b) |> (.count()));
+
+        assert_eq!(ret, 0);
+    }
+
+    #[test]
+    fn test_byte_order() {
+        assert_eq!(pike!(0x12345678u32 |> (swap_bytes)), 0x78563412);
+        assert_eq!(
+            pike!(0x12345678u32 |> (swap_bytes) |> (swap_bytes)),
+            0x12345678
+        );
+
+        let ret = pike!(0x12345678u32 |> (to_be));
+
+        assert_eq!(ret.to_ne_bytes(), [0x12, 0x34, 0x56, 0x78]);
+
+        let ret = pike!(0x12345678u32 |> (to_le));
+
+        assert_eq!(ret.to_ne_bytes(), [0x78, 0x56, 0x34, 0x12]);
+
+        if cfg!(target_endian = "little") {
+            assert_eq!(pike!(0x1234u16 |> (to_le)), 0x1234);
+        } else {
+            assert_eq!(pike!(0x1234u16 |> (to_be)), 0x1234);
+        }
+    }
+
+    #[test]
+    fn test_assert_matches() {
+        let some = Some(4);
+        let ret = pike!(some |> (assert_matches Some(_)) |> (.map(times2)));
+
+        assert_eq!(ret, Some(8));
+
+        let text = Some(String::from("pike"));
+        let ret = pike!(text |> (assert_matches Some(_name)));
+
+        assert_eq!(ret.as_deref(), Some("pike"));
+
+        let ret = pike!(3 |> (assert_matches 1 | 3 | 5) |> times2);
+
+        assert_eq!(ret, 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "pipeline value does not match `(assert_matches Some(0))`")]
+    fn test_assert_matches_panics() {
+        let some = Some(4);
+        pike!(some |> (assert_matches Some(0)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_to_vec() {
+        let numbers: &[u32] = &[1, 2, 3];
+        let ret: Vec<u32> = pike!(numbers |> (to_vec));
+
+        assert_eq!(ret, [1, 2, 3]);
+
+        let words = ["a", "b"];
+        let ret = pike!(words |> (to_vec) |> (tap_mut |v: &mut Vec<&str>| v.push("c")));
+
+        assert_eq!(ret, ["a", "b", "c"]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_probe() {
+        let ret = pike!(4 |> times2 |> (probe) |> times2);
+
+        assert_eq!(ret, 16);
+
+        let text = "pike";
+        let ret = pike!(text |> (chars) |> (collect::<Vec<_>>) |> (probe) |> (.len()));
+
+        assert_eq!(ret, 4);
+    }
+
+    #[test]
+    fn test_diff() {
+        let ret = pike!(4 |> (diff { |> times2 |> times2 }));
+
+        assert_eq!(ret, (4, 16));
+
+        let ret = pike! {
+            "b a c"
+            |> (split ' ')
+            |> (collect::<Vec<_>>)
+            |> (diff { |> (tap_mut |v: &mut Vec<&str>| v.sort()) })
+            |> (|(before, after)| before != after)
+        };
+
+        assert!(ret);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_rounding() {
+        assert_eq!(pike!(1.2f64 |> (ceil)), 2.0);
+        assert_eq!(pike!(1.8f64 |> (floor)), 1.0);
+        assert_eq!(pike!(1.5f32 |> (round)), 2.0);
+
+        let num = -1.5f64;
+        assert_eq!(pike!(num |> (ceil)), -1.0);
+        assert_eq!(pike!(num |> (floor)), -2.0);
+        assert_eq!(pike!(num |> (round)), -2.0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_float_math() {
+        assert_eq!(pike!(4.0f64 |> (sqrt)), 2.0);
+        assert_eq!(pike!(1.0f64 |> (ln)), 0.0);
+        assert_eq!(pike!(0.0f32 |> (exp)), 1.0);
+        assert!((pike!(1.0f64 |> (exp)) - core::f64::consts::E).abs() < 1e-12);
+        assert!(pike!(2.0f64 |> (exp) |> (ln) |> (|x: f64| (x - 2.0).abs() < 1e-12)));
+
+        let num = -1.0f64;
+        assert!(pike!(num |> (sqrt)).is_nan());
+    }
+
+    #[test]
+    fn test_from_str_radix() {
+        let hex = "1f";
+        let ret = pike!(hex |> (from_str_radix::<u32> 16) |> (.map(times2)));
+
+        assert_eq!(ret, Ok(62));
+
+        let ret = pike!("777" |> (from_str_radix 8));
+
+        assert_eq!(ret, Ok(511));
+
+        let ret = pike!("1g" |> (from_str_radix::<u32> 16));
+
+        assert!(ret.is_err());
+    }
+
+    #[test]
+    fn test_collect_result() {
+        let results: Vec<Result<u32, &str>> = vec![Ok(1), Ok(2)];
+        let ret = pike!(results |> (into_iter) |> (collect_result::<Vec<_>>));
+
+        assert_eq!(ret, Ok(vec![1, 2]));
+
+        let mut polled = 0;
+        let results: Vec<Result<u32, &str>> = vec![Ok(1), Err("bad"), Ok(3), Err("worse")];
+        let ret: Result<Vec<u32>, &str> = pike! {
+            results
+            |> (into_iter)
+            |> (.inspect(|_| polled += 1))
+            |> (collect_result)
+        };
+
+        assert_eq!(ret, Err("bad"));
+        assert_eq!(polled, 2);
+    }
+
+    #[test]
+    fn test_tap_err() {
+        let mut errors = 0;
+        let ret = pike!("4" |> (parse::<u32>) |> (tap_err |_| errors += 1) |> (.map(times2)));
+
+        assert_eq!(ret, Ok(8));
+        assert_eq!(errors, 0);
+
+        fn over_limit(n: u32) -> Result<u32, String> {
+            Err(format!("{n} is over the limit"))
+        }
+
+        let ret = pike! {
+            (pike_res!(4 |> over_limit))
+            |> (tap_err |e: &String| errors += e.len())
+        };
+
+        assert_eq!(ret, Err("4 is over the limit".to_string()));
+        assert_eq!(errors, 19);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_memoize() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let misses = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&misses);
+        let counted = move |n: u32| {
+            counter.set(counter.get() + 1);
+            times2(n)
+        };
+        let ret: Vec<u32> = [1, 2, 1, 1, 2, 3]
+            .into_iter()
+            .map(|n| pike!(n |> (memoize counted.clone()) |> times2))
+            .collect();
+
+        assert_eq!(ret, [4, 8, 4, 4, 8, 12]);
+        assert_eq!(misses.get(), 3);
+
+        let ret = pike!(1 |> (memoize counted.clone()));
+
+        assert_eq!(ret, 2);
+        assert_eq!(misses.get(), 4);
+
+        let text = String::from("pike");
+        let ret = pike!(text |> (memoize |s: String| s.len()));
+
+        assert_eq!(ret, 4);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_memoize_generic() {
+        fn call_with<F: Fn(u32) -> u32 + 'static>(n: u32, f: F) -> u32 {
+            pike!(n |> (memoize f))
+        }
+
+        assert_eq!(call_with(2, |x| x + 1), 3);
+        assert_eq!(call_with(2, |x| x * 10), 20);
+        assert_eq!(call_with(2, |x| x + 1), 3);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let numbers = vec![1, 2];
+        let ret = pike!(numbers |> (into_iter) |> (into_iter) |> (collect::<Vec<u32>>));
+
+        assert_eq!(ret, [1, 2]);
+
+        let some = Some(4);
+        let ret = pike!(some |> (into_iter) |> (.map(times2)) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, [8]);
+
+        let numbers = vec![3, 4];
+        let borrowed = &numbers;
+        let ret = pike!(borrowed |> (into_iter) |> (.copied()) |> (collect::<Vec<u32>>));
+
+        assert_eq!(ret, numbers);
+
+        let map = std::collections::BTreeMap::from([(1, "a"), (2, "b")]);
+        let ret = pike!(map |> (into_iter) |> (.map(|(k, _)| k)) |> (collect::<Vec<u32>>));
+
+        assert_eq!(ret, [1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_scan_collect() {
+        let range = 1..5;
+        let ret = pike!(range |> (scan_collect 0, |acc, x| acc + x));
+
+        assert_eq!(ret, [1, 3, 6, 10]);
+
+        let words = ["a", "b", "c"];
+        let ret = pike!(words |> (scan_collect String::new(), |acc, w| acc + w));
+
+        assert_eq!(ret, ["a", "ab", "abc"]);
+
+        let empty: [u32; 0] = [];
+        let ret = pike!(empty |> (scan_collect 0, |acc, x| acc + x));
+
+        assert!(ret.is_empty());
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut slot: Option<u32> = None;
+        let slot_ref = &mut slot;
+        let ret = pike!(slot_ref |> (get_or_insert_with || 4) |> (|n: &mut u32| times2(*n)));
+
+        assert_eq!(ret, 8);
+        assert_eq!(slot, Some(4));
+
+        let mut slot = Some(1);
+        let slot_ref = &mut slot;
+        let ret = pike!(slot_ref |> (get_or_insert_with || 4) |> (|n: &mut u32| *n));
+
+        assert_eq!(ret, 1);
+        assert_eq!(slot, Some(1));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_timed() {
+        let ret = pike!(4 |> (timed "times2", times2) |> (timed "closure", |i| i + 1));
+
+        assert_eq!(ret, 9);
+
+        let label = String::from("times");
+        let ret = pike!(2 |> (timed &label, |i| times(i, 3, 4)));
+
+        assert_eq!(ret, 24);
+    }
+
+    #[test]
+    fn test_chain() {
+        let range = 0..3;
+        let ret = pike!(range |> (chain 7..9) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, [0, 1, 2, 7, 8]);
+
+        let range = 0..2;
+        let ret = pike! {
+            range
+            |> (chain Some(5))
+            |> (chain vec![6])
+            |> (.map(times2))
+            |> (collect::<Vec<_>>)
+        };
+
+        assert_eq!(ret, [0, 2, 10, 12]);
+    }
+
+    #[test]
+    fn test_unzip() {
+        let range = 0..3;
+        let ret = pike!(range |> (.map(|i| (i, times2(i)))) |> (unzip::<Vec<_>, Vec<_>>));
+
+        assert_eq!(ret, (vec![0, 1, 2], vec![0, 2, 4]));
+
+        let words = ["a", "bb"];
+        let (words, lens): (Vec<&str>, Vec<usize>) = pike! {
+            words
+            |> (into_iter)
+            |> (.map(|w| (w, w.len())))
+            |> (unzip)
+        };
+
+        assert_eq!(words, ["a", "bb"]);
+        assert_eq!(lens, [1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_hex() {
+        let ret = pike!(0x1234u16 |> (hex) |> (swap_bytes) |> (hex));
+
+        assert_eq!(ret, 0x3412);
+
+        let flags = 0b1010u32;
+        let ret = pike!(flags |> (hex) |> (.count_ones()));
+
+        assert_eq!(ret, 2);
+    }
+
+    #[test]
+    fn test_tap_if() {
+        use std::cell::Cell;
+
+        let hits = Cell::new(0);
+        let ret = pike! {
+            60
+            |> times2
+            |> (tap_if |v| *v > 100, |v| hits.set(hits.get() + v))
             |> times2
-            |> (|i: u32| i * 2)
-            |> multiply
-            |> (test_macro_b!(10))
         };
 
-        assert_eq!(ret, Some(320));
+        assert_eq!(ret, 240);
+        assert_eq!(hits.get(), 120);
+
+        let ret = pike! {
+            20
+            |> times2
+            |> (tap_if |v| *v > 100, |v| hits.set(hits.get() + v))
+            |> times2
+        };
+
+        assert_eq!(ret, 80);
+        assert_eq!(hits.get(), 120);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_dbg_if() {
+        let ret = pike!(4 |> times2 |> (dbg_if |v| *v > 100) |> times2);
+
+        assert_eq!(ret, 16);
+
+        let ret = pike!(60 |> times2 |> (dbg_if |v| *v > 100) |> times2);
+
+        assert_eq!(ret, 240);
+    }
+
+    #[test]
+    fn test_as_slice() {
+        fn sum(values: &[u32]) -> u32 {
+            values.iter().sum()
+        }
+
+        let values = vec![1, 2, 3];
+        let ret = pike!(values |> (as_slice) |> sum |> times2);
+
+        assert_eq!(ret, 12);
+
+        let values = [4, 5];
+        let slice = pike!((&values) |> (as_slice));
+
+        assert_eq!(sum(slice), 9);
+        assert_eq!(values, [4, 5]);
+    }
+
+    #[test]
+    fn test_to_array() {
+        let values = [1, 2, 3, 4, 5];
+        let slice = &values[1..];
+        let ret = pike!(slice |> (to_array::<4>));
+
+        assert_eq!(ret.ok(), Some([2, 3, 4, 5]));
+
+        let slice = &values[2..];
+        let ret = pike!(slice |> (to_array::<4>));
+
+        assert!(ret.is_err());
+
+        let words = vec!["a".to_string(), "b".to_string()];
+        let ret = pike!(words |> (to_array::<2>));
+
+        assert_eq!(ret, Ok(["a".to_string(), "b".to_string()]));
+    }
+
+    #[test]
+    fn test_tee_to() {
+        let mut doubled = 0;
+        let mut ret = Vec::new();
+        for n in 1..4 {
+            let previous = doubled;
+            ret.push(pike!(n |> times2 |> (tee_to doubled) |> times2));
+
+            assert_eq!(doubled, previous + 2);
+        }
+
+        assert_eq!(ret, [4, 8, 12]);
+        assert_eq!(doubled, 6);
+    }
+
+    #[test]
+    fn test_tee_to_deferred_init() {
+        let doubled;
+        let ret = pike!(4 |> times2 |> (tee_to doubled) |> times2);
+
+        assert_eq!(ret, 16);
+        assert_eq!(doubled, 8);
+
+        let words = "b a";
+        let unsorted;
+        let ret = pike! {
+            words
+            |> (split ' ')
+            |> (collect::<Vec<_>>)
+            |> (tee_to unsorted)
+            |> (tap_mut |v| v.sort())
+        };
+
+        assert_eq!(ret, ["a", "b"]);
+        assert_eq!(unsorted, ["b", "a"]);
+    }
+
+    #[test]
+    fn test_select() {
+        let triple = (1, "two", 3u32);
+        let ret = pike!(triple |> (select 2) |> times2);
+
+        assert_eq!(ret, 6);
+        assert_eq!(pike!(triple |> (select 1)), "two");
+
+        let quad = ("a".to_string(), 2, 'c', [4]);
+        let ret = pike!((quad.clone()) |> (select 3) |> (.len()));
+
+        assert_eq!(ret, 1);
+        assert_eq!(pike!(quad |> (.0)), "a");
+    }
+
+    #[test]
+    fn test_field_access() {
+        #[derive(Clone, Copy)]
+        struct Point {
+            x: u32,
+            y: u32,
+        }
+
+        let point = Point { x: 2, y: 5 };
+        let ret = pike!(point |> (.x) |> times2);
+
+        assert_eq!(ret, 4);
+        assert_eq!(pike!(point |> (.y)), 5);
+    }
+
+    #[test]
+    fn test_map_while() {
+        let range = 1..10;
+        let ret = pike! {
+            range
+            |> (map_while |n: u32| 20u32.checked_sub(times2(n * n)))
+            |> (collect::<Vec<_>>)
+        };
+
+        assert_eq!(ret, [18, 12, 2]);
+    }
+
+    #[test]
+    fn test_take_while() {
+        let range = 0..10;
+        let ret = pike!(range |> (take_while |n| times2(*n) < 5) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, [0, 1, 2]);
+
+        let values = [3, 1, 7, 2];
+        let ret = pike!(values |> (.iter()) |> (take_while |n| **n < 5) |> (.sum::<u32>()));
+
+        assert_eq!(ret, 4);
+    }
+
+    #[test]
+    fn test_skip_while() {
+        let range = 0..10;
+        let ret = pike!(range |> (skip_while |n| times2(*n) < 15) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, [8, 9]);
+
+        let values = [3, 1, 7, 2];
+        let ret = pike!(values |> (.iter()) |> (skip_while |n| **n < 5) |> (.sum::<u32>()));
+
+        assert_eq!(ret, 9);
+    }
+
+    #[test]
+    fn test_partial_calls_of_operator_names() {
+        mod own {
+            pub fn take(a: u32, b: u32) -> u32 {
+                a - b
+            }
+
+            pub fn join(a: u32, b: u32, c: u32) -> u32 {
+                a * 100 + b * 10 + c
+            }
+
+            pub fn find(a: u32) -> u32 {
+                a + 1
+            }
+        }
+
+        let ret = pike!(5 |> (own::take(3)) |> (own::join(4, 2)) |> (own::find()));
+
+        assert_eq!(ret, 243);
+
+        let range = 0..10;
+        let ret = pike!(range |> (take 2 + 1) |> (collect::<Vec<_>>));
+
+        assert_eq!(ret, [0, 1, 2]);
     }
 }
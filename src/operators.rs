@@ -0,0 +1,898 @@
+//! Reference of the stages a pipeline accepts besides plain functions and paths.
+//!
+//! Every pipeline macro, [`pike!`](crate::pike), [`pike_res!`](crate::pike_res) and the others,
+//! understands the same stages. Operators marked *std*, *futures* or *tokio* need the feature of
+//! that name.
+//!
+//! An operator takes precedence over a function of the same name, so `(sort)` is the `sort`
+//! operator even when a `sort` function is in scope, while a bare `|> sort` or a path such as
+//! `(self::sort)` still calls the function. Operator arguments follow the name without
+//! parentheses, as in `(take 3)`. Since `(take(3))` would read as both the operator and a partial
+//! call of a `take` function, an operator name directly followed by parentheses is a compile
+//! error, and so is `(take (n + 1))`, which is written `(take n + 1)`. A function named after an
+//! operator is called by path instead, e.g. `(self::take(3))`.
+//!
+//! # Stage forms
+//!
+//! ## `(f(args))`
+//!
+//! A partial call `(f(a, b))` becomes `f(value, a, b)`. Macros can't see the signature of `f`, so
+//! a wrong number of arguments is reported as a plain call with too few or too many (`E0061`).
+//! ```rust,compile_fail,E0061
+//! use pike::pike;
+//! fn times(a: u32, b: u32, c: u32) -> u32 {
+//!     a * b * c
+//! }
+//!
+//! // `times` expects 3 arguments, the pipeline supplies 1 + 1.
+//! let n = pike!(2 |> (times(3)));
+//! ```
+//!
+//! ## `(f |args| body)`
+//!
+//! A function followed by a closure gets the value as the first argument and the closure as the
+//! last one. `(f move |args| body)` and `(f || body)` work as well.
+//! ```rust
+//! use pike::pike;
+//! fn with_doubled<T>(n: u32, f: impl FnOnce(u32) -> T) -> T {
+//!     f(n * 2)
+//! }
+//!
+//! let s = pike!(2 |> (with_doubled |n| n.to_string()));
+//! assert_eq!(s, "4");
+//! ```
+//!
+//! ## `(.method(args))`
+//!
+//! Calls a method on the value with method call syntax, so auto-deref applies and a method of `T`
+//! works on a `&&T`, `Box<T>` or `Rc<T>` too. Type arguments go in a turbofish.
+//! ```rust
+//! use pike::pike;
+//! use std::rc::Rc;
+//! let shared = Rc::new(String::from("abcd"));
+//! assert_eq!(pike!(shared |> (.len())), 4);
+//! ```
+//!
+//! ## `(.field)` and `(select index)`
+//!
+//! Move a field out of the value, `(.0)` or `(.name)`, with `(select 2)` spelling tuple access as
+//! a stage. The index is plain field access, so an index past the end is a compile error.
+//! ```rust
+//! use pike::pike;
+//! let row = ("pike", 3, 'x', 1.5);
+//! assert_eq!(pike!(row |> (select 2)), 'x');
+//! assert_eq!(pike!(row |> (.0) |> str::len), 4);
+//! ```
+//!
+//! ## `(<Type as Trait>::method(args))`
+//!
+//! Calls a trait method through its fully qualified path with the value as the receiver. The
+//! arguments can be left out as in `(<Type as Trait>::method)`.
+//! ```rust
+//! use pike::pike;
+//! trait Scale {
+//!     fn scale(self, by: u32) -> u32;
+//! }
+//!
+//! impl Scale for u32 {
+//!     fn scale(self, by: u32) -> u32 {
+//!         self * by
+//!     }
+//! }
+//!
+//! assert_eq!(pike!(2u32 |> (<u32 as Scale>::scale(3))), 6);
+//! ```
+//!
+//! ## `(dyn Trait::method(args))`
+//!
+//! Calls a `&self` method of a trait object, derefing the value to a `&dyn Trait` first so it
+//! works on `Box<dyn Trait>`, `Rc<dyn Trait>` and `&dyn Trait` alike.
+//! ```rust
+//! use pike::pike;
+//! trait Shape {
+//!     fn area(&self) -> f64;
+//! }
+//!
+//! struct Square(f64);
+//!
+//! impl Shape for Square {
+//!     fn area(&self) -> f64 {
+//!         self.0 * self.0
+//!     }
+//! }
+//!
+//! let shape: Box<dyn Shape> = Box::new(Square(2.0));
+//! assert_eq!(pike!(shape |> (dyn Shape::area)), 4.0);
+//! ```
+//!
+//! ## `(macro!(args))`
+//!
+//! A macro gets the value as its first argument, or in place of the first top-level `_` in the
+//! arguments. This also covers `write!` and `writeln!`, which forward the `Result` they return.
+//! ```rust
+//! use pike::pike;
+//! assert_eq!(pike!(3 |> (vec![_; 2])), [3, 3]);
+//! assert!(pike!(3 |> (matches!(_, 0..=5))));
+//! ```
+//!
+//! ## `(apply f)`
+//!
+//! Calls the function value `f`, such as an element of a table of boxed closures, with the value.
+//! `Box<dyn Fn>` values can also be piped into directly, as in `|> boxed`.
+//! ```rust
+//! use pike::pike;
+//! let ops: Vec<Box<dyn Fn(u32) -> u32>> = vec![Box::new(|n| n + 1), Box::new(|n| n * 10)];
+//! assert_eq!(pike!(2 |> (apply ops[1]) |> (apply ops[0])), 21);
+//! ```
+//!
+//! ## `(as type)`
+//!
+//! Casts the value with `as`, following its rules. Any type syntax works, including pointers and
+//! unsizing references.
+//! ```rust
+//! use pike::pike;
+//! let bytes = &[1u8, 2, 3, 4];
+//! assert_eq!(pike!(bytes |> (as &[u8])).len(), 4);
+//! ```
+//!
+//! # Values and control flow
+//!
+//! ## `(replace value)` and `(replace from, to)`
+//!
+//! With one argument the value is dropped and the pipeline carries on with `value`. With two it's
+//! the string method, replacing `from` with `to` in a new `String` (*std*).
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! assert_eq!(pike!("abcd" |> str::len |> (replace "done")), "done");
+//! assert_eq!(pike!("a-b-c" |> (replace "-", "/")), "a/b/c");
+//! ```
+//!
+//! ## `(eq value)`, `(ne value)`, `(lt value)`, `(le value)`, `(gt value)` and `(ge value)`
+//!
+//! Compare the value against `value` by reference, producing a `bool`. They need `PartialEq` or
+//! `PartialOrd`.
+//! ```rust
+//! use pike::pike;
+//! assert!(pike!("abcd" |> str::len |> (eq 4)));
+//! assert!(pike!(7 |> (gt 3)));
+//! ```
+//!
+//! ## `(tap_mut f)`
+//!
+//! Calls `f` with a mutable reference to the value and forwards the mutated value. The value is
+//! rebound as `mut` internally, so the source binding doesn't have to be.
+//! ```rust
+//! use pike::pike;
+//! let numbers = vec![3, 1, 2];
+//! assert_eq!(pike!(numbers |> (tap_mut |v| v.sort())), [1, 2, 3]);
+//! ```
+//!
+//! ## `(tap_if predicate, f)`
+//!
+//! Calls `f` with a reference to the value if `predicate` holds for it, and forwards the value
+//! either way.
+//! ```rust
+//! use pike::pike;
+//! use std::cell::Cell;
+//! let warnings = Cell::new(0);
+//! let total = pike!(150 |> (tap_if |v| *v > 100, |_| warnings.set(warnings.get() + 1)));
+//! assert_eq!((total, warnings.get()), (150, 1));
+//! ```
+//!
+//! ## `(tee { |> stage ... })`
+//!
+//! Runs the nested stages on a clone of the value, discards their result and forwards the
+//! original.
+//! ```rust
+//! use pike::pike;
+//! let mut log = Vec::new();
+//! let len = pike!("abcd" |> (tee { |> str::to_uppercase |> (|s| log.push(s)) }) |> str::len);
+//! assert_eq!((len, log), (4, vec!["ABCD".to_string()]));
+//! ```
+//!
+//! ## `(tee_to out)`
+//!
+//! Assigns a clone of the value to the place `out` and forwards the original. A binding has to be
+//! `mut` unless it's left uninitialized until the pipeline runs.
+//! ```rust
+//! use pike::pike;
+//! let doubled;
+//! let ret = pike!(4 |> (|n| n * 2) |> (tee_to doubled) |> (|n| n + 1));
+//! assert_eq!((ret, doubled), (9, 8));
+//! ```
+//!
+//! ## `(diff { |> stage ... })`
+//!
+//! Runs the nested stages on the value and produces a tuple of the value before and the result
+//! after them. The value has to be `Clone`.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! let (before, after) = pike!("Pike" |> (diff { |> (to_uppercase) }));
+//! assert_eq!((before, after.as_str()), ("Pike", "PIKE"));
+//! ```
+//!
+//! ## `(cond predicate => { |> stage ... } else { |> stage ... })`
+//!
+//! Sends the value through the first nested stages if `predicate` holds for a reference to it,
+//! and through the second ones otherwise. Both branches have to produce the same type.
+//! ```rust
+//! use pike::pike;
+//! let n = -3i32;
+//! let ret = pike!(n |> (cond |n| *n < 0 => { |> (abs) |> (pow 2) } else { |> (pow 3) }));
+//! assert_eq!(ret, 9);
+//! ```
+//!
+//! ## `(let_else pattern else diverge => output)`
+//!
+//! Destructures the value with `let pattern = value else diverge` and produces `output`. As with
+//! `let ... else` the `else` branch has to diverge.
+//! ```rust
+//! use pike::pike;
+//! fn last_code(s: &str) -> Option<u32> {
+//!     Some(pike!(s |> (chars) |> (.last()) |> (let_else Some(c) else return None => c as u32)))
+//! }
+//!
+//! assert_eq!(last_code("a"), Some(97));
+//! assert_eq!(last_code(""), None);
+//! ```
+//!
+//! ## `(try_block name => { ... })`
+//!
+//! Binds the value to `name` and runs the block inside a closure, so `?` leaves the block and the
+//! block evaluates to a `Result`. Other variables are only borrowed.
+//! ```rust
+//! use pike::pike;
+//! let doubled: Result<i32, std::num::ParseIntError> = pike! {
+//!     "21" |> (try_block text => { Ok(text.trim().parse::<i32>()? * 2) })
+//! };
+//! assert_eq!(doubled, Ok(42));
+//! ```
+//!
+//! ## `(build .method(args) ...)`
+//!
+//! Applies a chain of method calls to a mutable binding of the value in one stage, which suits
+//! builders whose methods take and return `&mut Self`.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! use std::process::Command;
+//! let args = pike!((Command::new("ls")) |> (build .arg("-l").arg("-a").get_args().len()));
+//! assert_eq!(args, 2);
+//! ```
+//!
+//! ## `(satisfies predicate)` and `(assert_matches pattern)`
+//!
+//! Forward the value unchanged, panicking with a message quoting the stage if `predicate` doesn't
+//! hold or the value doesn't match `pattern`. The pattern is matched against a reference.
+//! ```rust,should_panic
+//! use pike::pike;
+//! let parsed = pike!("4" |> (satisfies |s| s.is_ascii()) |> (parse::<u32>));
+//! pike!(parsed |> (assert_matches Ok(1..=9)) |> (.ok()) |> (assert_matches None));
+//! ```
+//!
+//! ## `(memoize f)` *std*
+//!
+//! Calls `f` with the value and caches the result keyed by the value in a thread local owned by
+//! the call site. `f` has to be pure and `'static`, the value `Eq + Hash + Clone`.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! let square = |n: u64| n * n;
+//! for _ in 0..3 {
+//!     assert_eq!(pike!(12u64 |> (memoize square)), 144);
+//! }
+//! ```
+//!
+//! ## `(send tx)` *std*
+//!
+//! Sends a clone of the value over the channel `tx` and forwards the original. A dropped receiver
+//! is ignored.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! let (tx, rx) = std::sync::mpsc::channel();
+//! assert_eq!(pike!(3u32 |> (send tx) |> (pow 2) |> (send tx)), 9);
+//! assert_eq!(rx.try_iter().collect::<Vec<_>>(), [3, 9]);
+//! ```
+//!
+//! ## `(catch_unwind f)` *std*
+//!
+//! Calls `f` with the value inside `std::panic::catch_unwind`, producing `Ok` with its result or
+//! `Err` with the panic payload.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! fn checked(n: u32) -> u32 {
+//!     assert!(n < 10, "too big");
+//!     n
+//! }
+//!
+//! # std::panic::set_hook(Box::new(|_| {}));
+//! assert!(pike!(40 |> (catch_unwind checked)).is_err());
+//! ```
+//!
+//! # Iterators
+//!
+//! Adapters go between a stage starting an iterator, such as `(into_iter)`, and a terminal stage
+//! such as `(collect)`.
+//!
+//! ## `(iter)`, `(into_iter)` and `(collect)`
+//!
+//! Start an iterator and collect it back, with the collection inferred or given as in
+//! `(collect::<Vec<_>>)`. Iterator stages never add an `(into_iter)` on their own.
+//! ```rust
+//! use pike::pike;
+//! let numbers = vec![1, 2, 3];
+//! let doubled = pike!(numbers |> (into_iter) |> (.map(|n| n * 2)) |> (collect::<Vec<_>>));
+//! assert_eq!(doubled, [2, 4, 6]);
+//! ```
+//!
+//! ## `(take n)`, `(skip n)` and `(step_by n)`
+//!
+//! Keep the first `n` items, skip over them, or yield every `n`th item starting with the first.
+//! ```rust
+//! use pike::pike;
+//! let range = 0..10;
+//! let ret = pike!(range |> (skip 2) |> (step_by 2) |> (take 3) |> (collect::<Vec<_>>));
+//! assert_eq!(ret, [2, 4, 6]);
+//! ```
+//!
+//! ## `(take_while predicate)` and `(skip_while predicate)`
+//!
+//! Yield the items before, or from, the first one failing `predicate`, which gets a reference to
+//! each item.
+//! ```rust
+//! use pike::pike;
+//! let range = 1..8;
+//! assert_eq!(pike!(range |> (take_while |n| *n < 4) |> (collect::<Vec<_>>)), [1, 2, 3]);
+//! ```
+//!
+//! ## `(map_while f)`
+//!
+//! Maps the items with `f` and stops at the first `None` it returns.
+//! ```rust
+//! use pike::pike;
+//! let counts = [3u32, 1, 0, 2];
+//! let left = pike!(counts |> (into_iter) |> (map_while |n| n.checked_sub(1)) |> (.count()));
+//! assert_eq!(left, 2);
+//! ```
+//!
+//! ## `(flat_map f)` and `(flatten)`
+//!
+//! `(flat_map f)` maps every item to something iterable and flattens the results. `(flatten)`
+//! removes one level of nesting from an iterator or an `Option`.
+//! ```rust
+//! use pike::pike;
+//! let range = 1..4;
+//! let repeated = pike!(range |> (flat_map |n| vec![n; n]) |> (collect::<Vec<_>>));
+//! assert_eq!(repeated, [1, 2, 2, 3, 3, 3]);
+//! assert_eq!(pike!((Some(Some(4))) |> (flatten)), Some(4));
+//! ```
+//!
+//! ## `(enumerate)` and `(rev)`
+//!
+//! Pair every item with its index, or reverse a `DoubleEndedIterator`.
+//! ```rust
+//! use pike::pike;
+//! let indexed = pike!("ab" |> (chars) |> (rev) |> (enumerate) |> (collect::<Vec<_>>));
+//! assert_eq!(indexed, [(0, 'b'), (1, 'a')]);
+//! ```
+//!
+//! ## `(scan state, f)`
+//!
+//! Threads a mutable `state` through the items, yielding what `f(&mut state, item)` returns until
+//! it returns `None`.
+//! ```rust
+//! use pike::pike;
+//! let range = 1..5;
+//! let sums = pike!(range |> (scan 0, |sum, n| { *sum += n; Some(*sum) }) |> (collect::<Vec<_>>));
+//! assert_eq!(sums, [1, 3, 6, 10]);
+//! ```
+//!
+//! ## `(chain other)` and `(zip_with other, f)`
+//!
+//! `(chain other)` yields the items of `other` after those of the iterator. `(zip_with other, f)`
+//! combines the items of both pairwise through `f` and ends with the shorter one.
+//! ```rust
+//! use pike::pike;
+//! let head = [1, 2];
+//! let ret = pike!(head |> (into_iter) |> (chain [3, 4]) |> (zip_with [10, 10], |a, b| a * b));
+//! assert_eq!(ret.collect::<Vec<_>>(), [10, 20]);
+//! ```
+//!
+//! ## `(cycle)` and `(peekable)`
+//!
+//! `(cycle)` repeats a `Clone` iterator endlessly, so it needs a bounding adapter such as
+//! `(take n)` before a terminal stage. `(peekable)` turns an iterator into a `Peekable` one.
+//! ```rust
+//! use pike::pike;
+//! let pattern = [1, 2];
+//! let repeated = pike!(pattern |> (into_iter) |> (cycle) |> (take 3) |> (collect::<Vec<_>>));
+//! assert_eq!(repeated, [1, 2, 1]);
+//! ```
+//!
+//! ## `(position predicate)`
+//!
+//! Produces the index of the first item `predicate` holds for, or `None`.
+//! ```rust
+//! use pike::pike;
+//! let letters = ['a', 'b', 'c'];
+//! assert_eq!(pike!(letters |> (into_iter) |> (position |c| c == 'b')), Some(1));
+//! ```
+//!
+//! ## `(find predicate)`, `(any predicate)` and `(all predicate)`
+//!
+//! `(find predicate)` produces the first item `predicate` holds for a reference to, `(any ..)`
+//! and `(all ..)` take the items by value and produce a `bool`.
+//! ```rust
+//! use pike::pike;
+//! let numbers = [1, 2, 3];
+//! assert_eq!(pike!(numbers |> (into_iter) |> (find |n| *n > 1)), Some(2));
+//! assert!(pike!(numbers |> (into_iter) |> (any |n| n > 2)));
+//! ```
+//!
+//! ## `(reduce f)` and `(try_fold init, f)`
+//!
+//! `(reduce f)` folds the items with the first one as the initial accumulator, producing `None`
+//! for an empty iterator. `(try_fold init, f)` folds with a fallible `f` and stops at the first
+//! error.
+//! ```rust
+//! use pike::pike;
+//! let numbers = [200u8, 100];
+//! assert_eq!(pike!(numbers |> (into_iter) |> (reduce |a, b| a / b)), Some(2));
+//! assert_eq!(pike!(numbers |> (into_iter) |> (try_fold 0u8, |a, n| a.checked_add(n))), None);
+//! ```
+//!
+//! ## `(max_by_key f)` and `(min_by_key f)`
+//!
+//! Produce the item with the largest or smallest key `f` computes from a reference to it. Ties go
+//! to the last item for `max_by_key` and to the first for `min_by_key`.
+//! ```rust
+//! use pike::pike;
+//! let words = ["pike", "pipe", "operator"];
+//! assert_eq!(pike!(words |> (into_iter) |> (min_by_key |w| w.len())), Some("pike"));
+//! ```
+//!
+//! ## `(collect_result)`
+//!
+//! Collects an iterator of `Result`s into `Ok` with a collection of the values, or the first
+//! `Err`. The collection is inferred or given as in `(collect_result::<Vec<_>>)`.
+//! ```rust
+//! use pike::pike;
+//! let texts = ["1", "2"];
+//! let numbers = pike!(texts |> (into_iter) |> (.map(str::parse)) |> (collect_result::<Vec<u32>>));
+//! assert_eq!(numbers, Ok(vec![1, 2]));
+//! ```
+//!
+//! ## `(unzip)`
+//!
+//! Splits an iterator of pairs into two collections, inferred or given as in
+//! `(unzip::<Vec<_>, Vec<_>>)`.
+//! ```rust
+//! use pike::pike;
+//! let pairs = [(1, 'a'), (2, 'b')];
+//! let (numbers, letters) = pike!(pairs |> (into_iter) |> (unzip::<Vec<_>, String>));
+//! assert_eq!((numbers, letters.as_str()), (vec![1, 2], "ab"));
+//! ```
+//!
+//! ## `(join separator)` *std*
+//!
+//! Joins an iterator of strings with `separator` into a `String`.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! let words = ["a", "b", "c"];
+//! assert_eq!(pike!(words |> (into_iter) |> (join ", ")), "a, b, c");
+//! ```
+//!
+//! ## `(counts)` and `(group_by f)` *std*
+//!
+//! Aggregate into a `HashMap`, of every distinct item to how often it occurs, or of every key `f`
+//! computes from a reference to an item to a `Vec` of the items sharing it.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! assert_eq!(pike!("hello" |> (chars) |> (counts))[&'l'], 2);
+//!
+//! let words = ["apple", "avocado", "banana"];
+//! let by_letter = pike!(words |> (group_by |w: &&str| w.as_bytes()[0]));
+//! assert_eq!(by_letter[&b'a'], ["apple", "avocado"]);
+//! ```
+//!
+//! ## `(sorted)`, `(dedup)` and `(unique)` *std*
+//!
+//! `(sorted)` collects into a sorted `Vec`. `(dedup)` drops consecutive duplicates of a `Vec` or
+//! slice, `(unique)` all duplicates of anything iterable keeping the first occurrence.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! assert_eq!(pike!("pike" |> (chars) |> (sorted)), ['e', 'i', 'k', 'p']);
+//! assert_eq!(pike!((vec![1, 1, 2, 1]) |> (dedup)), [1, 2, 1]);
+//! assert_eq!(pike!((vec![1, 1, 2, 1]) |> (unique)), [1, 2]);
+//! ```
+//!
+//! ## `(scan_collect init, f)` *std*
+//!
+//! Folds the items starting from `init` and collects the state after each item into a `Vec`,
+//! which doesn't include `init` itself.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! let deposits = [10, 5, 20];
+//! assert_eq!(pike!(deposits |> (scan_collect 100, |balance, n| balance + n)), [110, 115, 135]);
+//! ```
+//!
+//! # Slices and arrays
+//!
+//! The value of a slice stage has to be a slice or deref to one, and the results borrow from it.
+//!
+//! ## `(sort)`
+//!
+//! Sorts a `Vec` or an array in place and forwards it.
+//! ```rust
+//! use pike::pike;
+//! let numbers = [3, 1, 2];
+//! assert_eq!(pike!(numbers |> (sort)), [1, 2, 3]);
+//! ```
+//!
+//! ## `(windows n)`, `(chunks n)` and `(chunk_by predicate)`
+//!
+//! Iterate over overlapping windows or chunks of length `n`, or over runs of neighbours
+//! `predicate` holds for.
+//! ```rust
+//! use pike::pike;
+//! let numbers: &[u32] = &[1, 1, 2, 3];
+//! assert_eq!(pike!(numbers |> (windows 3) |> (collect::<Vec<_>>)), [[1, 1, 2], [1, 2, 3]]);
+//! assert_eq!(pike!(numbers |> (chunks 3) |> (collect::<Vec<_>>)), [&[1, 1, 2][..], &[3]]);
+//! assert_eq!(pike!(numbers |> (chunk_by |a, b| a == b) |> (.count())), 3);
+//! ```
+//!
+//! ## `(split_at n)`, `(first)` and `(last)`
+//!
+//! Split a slice at index `n`, panicking past its end, or produce an `Option` of its first or
+//! last element.
+//! ```rust
+//! use pike::pike;
+//! let digits: &[u32] = &[1, 2, 3];
+//! assert_eq!(pike!(digits |> (split_at 1)), (&[1][..], &[2, 3][..]));
+//! assert_eq!(pike!(digits |> (last)), Some(&3));
+//! ```
+//!
+//! ## `(as_slice)` and `(to_vec)`
+//!
+//! Borrow a `Vec` or an array as a slice, or copy a slice into a new `Vec` (*std*).
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! let values = vec![3, 1, 2];
+//! assert_eq!(pike!((&values) |> (as_slice) |> (to_vec) |> (tap_mut |v| v.push(4))), [3, 1, 2, 4]);
+//! ```
+//!
+//! ## `(to_array::<N>)`
+//!
+//! Converts the value into an array of `N` elements with `TryFrom`, failing if the length doesn't
+//! match.
+//! ```rust
+//! use pike::pike;
+//! let bytes: &[u8] = &[0, 0, 1, 0];
+//! assert_eq!(pike!(bytes |> (to_array::<4>) |> (.map(u32::from_be_bytes))).ok(), Some(256));
+//! ```
+//!
+//! ## `(array_from_fn)`
+//!
+//! Builds an array by calling the piped closure with every index, with the length inferred.
+//! ```rust
+//! use pike::pike;
+//! let square = |i: usize| i * i;
+//! let squares: [usize; 4] = pike!(square |> (array_from_fn));
+//! assert_eq!(squares, [0, 1, 4, 9]);
+//! ```
+//!
+//! # Strings
+//!
+//! ## `(split separator)`, `(lines)`, `(chars)` and `(bytes)`
+//!
+//! Start an iterator over the pieces between `separator`, the lines, the `char`s or the bytes of a
+//! `&str`. Pieces and lines borrow from the string.
+//! ```rust
+//! use pike::pike;
+//! let line = "a,b";
+//! assert_eq!(pike!(line |> (split ',') |> (collect::<Vec<_>>)), ["a", "b"]);
+//! assert_eq!(pike!("one\ntwo" |> (lines) |> (.count())), 2);
+//! assert_eq!(pike!("ab" |> (bytes) |> (collect::<Vec<_>>)), [97, 98]);
+//! ```
+//!
+//! ## `(trim)`, `(to_lowercase)` and `(to_uppercase)`
+//!
+//! `(trim)` strips surrounding whitespace, borrowing from the value. The case mappings produce a
+//! new `String` (*std*).
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! assert_eq!(pike!("  Pike \n" |> (trim) |> (to_lowercase)), "pike");
+//! ```
+//!
+//! ## `(parse)` and `(from_str_radix radix)`
+//!
+//! Parse a `&str` into any `FromStr` type, or into an integer in base `radix`, producing a
+//! `Result`. The type is inferred or given as in `(parse::<i32>)`, and is `i64` for
+//! `from_str_radix` otherwise.
+//! ```rust
+//! use pike::pike;
+//! assert_eq!(pike!("42" |> (parse::<i32>)), Ok(42));
+//! assert_eq!(pike!("ff" |> (from_str_radix::<u8> 16)), Ok(255));
+//! ```
+//!
+//! ## `(as_bytes)` and `(from_utf8)`
+//!
+//! View a `&str` as bytes, or validate bytes as UTF-8 into a `Result` with the `&str`.
+//! ```rust
+//! use pike::pike;
+//! let bytes = pike!("pike" |> (as_bytes));
+//! assert_eq!(pike!(bytes |> (from_utf8)), Ok("pike"));
+//! ```
+//!
+//! # Options and results
+//!
+//! ## `(or value)`
+//!
+//! Falls back to the `Option` or `Result` `value` on `None` or `Err`, keeping the wrapper.
+//! ```rust
+//! use pike::pike;
+//! assert_eq!(pike!(None |> (or Some(5))), Some(5));
+//! ```
+//!
+//! ## `(recover f)` and `(recover_default)`
+//!
+//! Unwrap a `Result` or `Option`, computing the fallback from the error with `f`, or falling back
+//! to `Default::default()`.
+//! ```rust
+//! use pike::pike;
+//! assert_eq!(pike!("four" |> (parse::<u32>) |> (recover |_| 4)), 4);
+//! assert_eq!(pike!("four" |> (parse::<u32>) |> (recover_default)), 0);
+//! ```
+//!
+//! ## `(expect_with f)`
+//!
+//! Unwraps a `Result` or `Option`, panicking with the message `f` computes from the error, or
+//! without arguments for an `Option`.
+//! ```rust,should_panic
+//! use pike::pike;
+//! let n = pike!("four" |> (parse::<u32>) |> (expect_with |e| format!("not a number: {e}")));
+//! ```
+//!
+//! ## `(on_some f)` and `(on_ok f)`
+//!
+//! Map the `Some` or `Ok` value with `f`. Piping the other wrapper into them is a type error at the
+//! stage.
+//! ```rust
+//! use pike::pike;
+//! assert_eq!(pike!((Some(2)) |> (on_some |n| n * 2)), Some(4));
+//! assert_eq!(pike!("2" |> (parse::<u32>) |> (on_ok |n| n * 2)), Ok(4));
+//! ```
+//!
+//! ## `(map_or default, f)`
+//!
+//! Produces `f` of the `Some` or `Ok` value, or the eagerly evaluated `default`.
+//! ```rust
+//! use pike::pike;
+//! assert_eq!(pike!("x" |> (parse::<u32>) |> (map_or 0, |n| n * 2)), 0);
+//! ```
+//!
+//! ## `(transpose)`
+//!
+//! Swaps an `Option<Result<T, E>>` into a `Result<Option<T>, E>` and back.
+//! ```rust
+//! use pike::pike;
+//! let parsed: Result<Option<u32>, _> = pike!((Some("42")) |> (.map(str::parse)) |> (transpose));
+//! assert_eq!(parsed, Ok(Some(42)));
+//! ```
+//!
+//! ## `(into_result err)`
+//!
+//! Turns an `Option` into a `Result` with `err` for `None`, like `Option::ok_or`.
+//! ```rust
+//! use pike::pike;
+//! assert_eq!(pike!("ab" |> (chars) |> (.last()) |> (into_result "empty")), Ok('b'));
+//! ```
+//!
+//! ## `(guard predicate, error)`
+//!
+//! Produces `Ok(value)` if `predicate` holds for a reference to it and `Err(error)` otherwise. In
+//! `pike_res` a failing guard ends the pipeline.
+//! ```rust
+//! use pike::pike_res;
+//! fn times2(n: u32) -> Result<u32, &'static str> {
+//!     Ok(n * 2)
+//! }
+//!
+//! assert_eq!(pike_res!(4 |> times2 |> (guard |n| *n <= 4, "too big")), Err("too big"));
+//! ```
+//!
+//! ## `(validate)`
+//!
+//! Checks the value with its [`PikeValidate`](crate::PikeValidate) implementation, producing the
+//! `Result` of `validate`.
+//! ```rust
+//! use pike::{pike, PikeValidate};
+//! struct Percent(u8);
+//!
+//! impl PikeValidate for Percent {
+//!     type Error = &'static str;
+//!
+//!     fn validate(self) -> Result<Self, Self::Error> {
+//!         if self.0 <= 100 { Ok(self) } else { Err("more than 100%") }
+//!     }
+//! }
+//!
+//! assert!(pike!((Percent(120)) |> (validate)).is_err());
+//! ```
+//!
+//! ## `(into_res { |> stage ... })`
+//!
+//! Runs the nested stages on an `Ok` value with `pike_res` semantics and forwards an `Err` as is.
+//! ```rust
+//! use pike::pike;
+//! fn checked_double(n: u32) -> Result<u32, &'static str> {
+//!     n.checked_mul(2).ok_or("overflow")
+//! }
+//!
+//! let doubled = pike! {
+//!     "21"
+//!     |> (parse::<u32>)
+//!     |> (.map_err(|_| "not a number"))
+//!     |> (into_res { |> checked_double })
+//! };
+//! assert_eq!(doubled, Ok(42));
+//! ```
+//!
+//! ## `(tap_err f)`
+//!
+//! Calls `f` with a reference to the error of a `Result` and forwards the `Result` unchanged.
+//! ```rust
+//! use pike::pike;
+//! let mut failures = 0;
+//! let port = pike!("eighty" |> (parse::<u16>) |> (tap_err |_| failures += 1) |> (.unwrap_or(80)));
+//! assert_eq!((port, failures), (80, 1));
+//! ```
+//!
+//! ## `(flatten_errors)` *std*
+//!
+//! Boxes the error of a `Result` into a `Box<dyn std::error::Error>`, so stages with different
+//! error types can share one `Result`.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! use std::error::Error;
+//! let port: Result<u16, Box<dyn Error>> = pike!("80" |> (parse::<u16>) |> (flatten_errors));
+//! assert_eq!(port.unwrap(), 80);
+//! ```
+//!
+//! ## `(get_or_insert_with f)`
+//!
+//! Stores the result of `f` in a `&mut Option<T>` that is `None` and produces a `&mut T` to the
+//! contained value.
+//! ```rust
+//! use pike::pike;
+//! let mut cache: Option<Vec<u32>> = None;
+//! let slot = &mut cache;
+//! pike!(slot |> (get_or_insert_with Vec::new) |> (.push(1)));
+//! assert_eq!(cache, Some(vec![1]));
+//! ```
+//!
+//! # Numbers
+//!
+//! ## `(abs)`, `(signum)` and `(pow n)`
+//!
+//! Call the method of the same name, with the exponent of `(pow n)` always a `u32`. The numeric
+//! type has to be known, so literals may need a suffix.
+//! ```rust
+//! use pike::pike;
+//! assert_eq!(pike!((-3i32) |> (abs) |> (pow 2)), 9);
+//! ```
+//!
+//! ## `(checked_add x)`, `(checked_sub x)`, `(checked_mul x)` and `(checked_div x)`
+//!
+//! Integer arithmetic producing `None` on overflow or division by zero, which fits `pike_opt`.
+//! ```rust
+//! use pike::pike;
+//! assert_eq!(pike!(250u8 |> (checked_add 6)), None);
+//! ```
+//!
+//! ## `(saturating_add x)`, `(wrapping_add x)` and the other saturating and wrapping stages
+//!
+//! Integer arithmetic clamping to the bounds of the type or wrapping around them, with `sub` and
+//! `mul` variants of both.
+//! ```rust
+//! use pike::pike;
+//! assert_eq!(pike!(250u8 |> (saturating_add 10)), 255);
+//! assert_eq!(pike!(250u8 |> (wrapping_add 10)), 4);
+//! ```
+//!
+//! ## `(swap_bytes)`, `(to_be)` and `(to_le)`
+//!
+//! Reverse the bytes of an integer, or convert it from the native byte order to big or little
+//! endian.
+//! ```rust
+//! use pike::pike;
+//! assert_eq!(pike!(0x1234u16 |> (swap_bytes)), 0x3412);
+//! assert_eq!(pike!(0x1234u16 |> (to_be) |> (.to_ne_bytes())), [0x12, 0x34]);
+//! ```
+//!
+//! ## `(ceil)`, `(floor)`, `(round)`, `(sqrt)`, `(ln)` and `(exp)` *std*
+//!
+//! Float rounding and functions, following IEEE 754 for inputs out of their range.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! assert_eq!(pike!(2.5f64 |> (round) |> (as u32)), 3);
+//! assert_eq!(pike!(16.0f64 |> (sqrt) |> (sqrt)), 2.0);
+//! ```
+//!
+//! # Debugging
+//!
+//! Debugging stages print to stderr, prefixed with the location of the pipeline like `dbg!`, and
+//! forward the value unchanged. All of them need *std*.
+//!
+//! ## `(dbg_type)`, `(probe)`, `(debug_pretty)` and `(hex)`
+//!
+//! Print the type of the value, the value with `{:?}` followed by its type, the value with
+//! `{:#?}`, or the value with `{:#x}`.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! let len = pike!("pike" |> str::len |> (dbg_type) |> (probe) |> (debug_pretty) |> (hex));
+//! assert_eq!(len, 4);
+//! ```
+//!
+//! ## `(dbg_if predicate)`
+//!
+//! Prints the value with `{:?}` only if `predicate` holds for a reference to it.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! assert_eq!(pike!(3 |> (i32::wrapping_sub(5)) |> (dbg_if |d| *d < 0)), -2);
+//! ```
+//!
+//! ## `(timed label, f)`
+//!
+//! Calls `f` with the value and prints how long only that call took, labelled with `label`.
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```rust,ignore")]
+//! use pike::pike;
+//! assert_eq!(pike!(" 42 " |> (timed "trim", str::trim)), "42");
+//! ```
+//!
+//! # Async
+//!
+//! Async stages await the value, so the pipeline has to run in an async context.
+//!
+//! ## `(join_all)` *futures*
+//!
+//! Awaits a tuple of up to five futures concurrently and produces a tuple of their outputs.
+//! ```rust,ignore
+//! use pike::pike;
+//! let (user, posts) = pike!((fetch_user(id), fetch_posts(id)) |> (join_all));
+//! ```
+//!
+//! ## `(timeout duration)` *tokio*
+//!
+//! Awaits the future, producing `Err(Elapsed)` and dropping it if it takes longer than
+//! `duration`.
+//! ```rust,ignore
+//! use pike::pike;
+//! use std::time::Duration;
+//! let user = pike!((fetch_user(id)) |> (timeout Duration::from_secs(1)))?;
+//! ```
+//!
+//! ## `(retry_with backoff, retries, f)` *tokio*
+//!
+//! Awaits the async `f` on a clone of the value, sleeping `backoff` and retrying up to `retries`
+//! times while it produces an `Err`.
+//! ```rust,ignore
+//! use pike::pike;
+//! use std::time::Duration;
+//! let user = pike!(id |> (retry_with Duration::from_millis(100), 3, fetch_user))?;
+//! ```
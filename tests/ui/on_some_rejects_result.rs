@@ -0,0 +1,10 @@
+use pike::pike;
+
+fn times2(n: u32) -> u32 {
+    n * 2
+}
+
+fn main() {
+    let parsed: Result<u32, std::num::ParseIntError> = "2".parse();
+    let _ = pike!(parsed |> (on_some times2));
+}
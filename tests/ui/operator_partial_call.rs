@@ -0,0 +1,6 @@
+use pike::pike;
+
+fn main() {
+    // `take` is an operator, so `(take(2))` is neither the operator nor a call of a `take` function.
+    let _ = pike!((1..4) |> (take(2)) |> (collect::<Vec<_>>));
+}
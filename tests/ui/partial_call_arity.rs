@@ -0,0 +1,10 @@
+use pike::pike;
+
+fn times(a: u32, b: u32, c: u32) -> u32 {
+    a * b * c
+}
+
+fn main() {
+    // `times` takes three arguments but the pipeline only supplies 1 + 1.
+    let _ = pike!(2 |> (times(3)));
+}
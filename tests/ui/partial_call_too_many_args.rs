@@ -0,0 +1,10 @@
+use pike::pike;
+
+fn times(a: u32, b: u32) -> u32 {
+    a * b
+}
+
+fn main() {
+    // `times` takes two arguments but the pipeline supplies 1 + 2.
+    let _ = pike!(2 |> (times(3, 4)));
+}
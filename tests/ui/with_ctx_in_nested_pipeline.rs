@@ -0,0 +1,11 @@
+use pike::pike_ctx;
+
+fn scale(n: u32, factor: &u32) -> u32 {
+    n * factor
+}
+
+fn main() {
+    // Nested pipelines run as plain `pike`, so they don't see the context.
+    let factor = 3;
+    let _ = pike_ctx!(factor; 2 |> (tee { |> (with_ctx scale) }));
+}